@@ -0,0 +1,86 @@
+use crate::output::{self, say};
+use crate::utils::hex_color;
+use anyhow::Result;
+use colored::Colorize;
+use libtea_gpg_wallet::config::{Config, Setting};
+use serde_json::json;
+
+fn source_label<T>(setting: &Setting<T>) -> String {
+    format!("({})", setting.source).dimmed().to_string()
+}
+
+pub(crate) fn handle_config(config: &Config) -> Result<()> {
+    let network = &config.network.value;
+    output::emit(json!({
+        "configFile": config.config_path.as_ref().map(|path| path.display().to_string()),
+        "network": network.name,
+        "networkSource": config.network.source.to_string(),
+        "chainId": network.chain_id,
+        "nativeSymbol": network.native_symbol,
+        "explorerUrl": network.explorer_url.as_ref().map(|url| url.to_string()),
+        "rpcUrl": config.rpc_url.value.to_string(),
+        "rpcUrlSource": config.rpc_url.source.to_string(),
+        "wsUrl": network.ws_url.as_ref().map(|url| url.to_string()),
+        "deployer": config.deployer.value,
+        "deployerSource": config.deployer.source.to_string(),
+        "alternateRpcUrls": network.rpc_urls.iter().skip(1).map(|url| url.to_string()).collect::<Vec<_>>(),
+        "networks": config
+            .networks
+            .iter()
+            .map(|(name, profile)| json!({ "name": name, "chainId": profile.chain_id }))
+            .collect::<Vec<_>>(),
+    }));
+    say!("Effective configuration:\n");
+    say!("{}", "Config file:".blue().bold());
+    match &config.config_path {
+        Some(path) => say!("  {}", path.display().to_string().green()),
+        None => say!("  {}", "none".dimmed()),
+    }
+    say!("{}", "Network:".blue().bold());
+    say!(
+        "  {} {}",
+        network.name.green(),
+        source_label(&config.network)
+    );
+    say!("{}", "Chain ID:".blue().bold());
+    say!("  {}", network.chain_id.to_string().green());
+    say!("{}", "Native symbol:".blue().bold());
+    say!("  {}", network.native_symbol.green());
+    say!("{}", "Block explorer:".blue().bold());
+    match &network.explorer_url {
+        Some(url) => say!("  {}", url.to_string().green()),
+        None => say!("  {}", "none".dimmed()),
+    }
+    say!("{}", "RPC URL:".blue().bold());
+    say!(
+        "  {} {}",
+        config.rpc_url.value.to_string().green(),
+        source_label(&config.rpc_url)
+    );
+    if let Some(url) = &network.ws_url {
+        say!("{}", "WebSocket URL:".blue().bold());
+        say!("  {}", url.to_string().green());
+    }
+    say!("{}", "Deployer address:".blue().bold());
+    say!(
+        "  {} {}",
+        hex_color(config.deployer.value.to_string(), 4)?,
+        source_label(&config.deployer)
+    );
+    if network.rpc_urls.len() > 1 {
+        say!("{}", "Alternate RPC URLs:".blue().bold());
+        for url in &network.rpc_urls[1..] {
+            say!("  {}", url.to_string().dimmed());
+        }
+    }
+    say!("{}", "Available networks:".blue().bold());
+    for (name, profile) in &config.networks {
+        let marker = if name == &network.name { "*" } else { " " };
+        say!(
+            "{marker} {} {}",
+            name.green(),
+            format!("(chain ID {})", profile.chain_id).dimmed()
+        );
+    }
+    Ok(())
+}
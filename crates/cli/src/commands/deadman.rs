@@ -0,0 +1,101 @@
+use crate::commands::{connect_sender, get_key_id, with_tx};
+use crate::output::{self, say};
+use crate::utils::{
+    format_duration, format_native, format_unix_time, hex_color, require_private_key,
+    wei_to_eth_auto,
+};
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::{Context, Result, anyhow};
+use clap::ArgMatches;
+use colored::Colorize;
+use libtea_gpg_wallet::{client::GpgWalletClient, network::Network, utils::unix_now};
+use serde_json::json;
+
+pub(crate) async fn handle_deadman(sub_m: &ArgMatches, network: &Network) -> Result<()> {
+    let key_id = get_key_id(sub_m).await?;
+    let recover = sub_m.get_flag("recover");
+    let client = if recover {
+        connect_sender(sub_m, network)?
+    } else {
+        GpgWalletClient::connect(network)
+    };
+    let switch = client.deadman_switch(&key_id).await?;
+    let now = unix_now();
+    let mut record = json!({
+        "keyId": key_id,
+        "admin": switch.admin,
+        "triggerDate": switch.trigger_date,
+        "fired": switch.is_triggered(now),
+        "remainingSecs": switch.remaining(now),
+    });
+    say!(
+        "{} {}:",
+        "Dead man switch for key ID".blue().bold(),
+        hex_color(&key_id, 4)?
+    );
+    say!(
+        "\t{}: {}",
+        "Admin".blue().bold(),
+        hex_color(switch.admin.to_string(), 4)?
+    );
+    say!(
+        "\t{}: {}",
+        "Trigger date".blue().bold(),
+        format_unix_time(switch.trigger_date)
+    );
+    if switch.is_triggered(now) {
+        say!(
+            "\t{}: {}",
+            "Status".blue().bold(),
+            "fired, the admin can recover the wallet's funds"
+                .red()
+                .bold()
+        );
+    } else {
+        say!(
+            "\t{}: {} ({} remaining)",
+            "Status".blue().bold(),
+            "not fired".green().bold(),
+            format_duration(switch.remaining(now))
+        );
+    }
+    if !recover {
+        output::emit(record);
+        return Ok(());
+    }
+
+    let signer = require_private_key()?.parse::<PrivateKeySigner>()?;
+    if signer.address() != switch.admin {
+        return Err(anyhow!(
+            "Only the admin {} can recover this wallet, not {}",
+            switch.admin,
+            signer.address()
+        ));
+    }
+    client.verify_chain_id().await?;
+    let pb = output::spinner();
+    pb.set_message(format!(
+        "Recovering funds from key ID {} to {}",
+        hex_color(&key_id, 4)?,
+        hex_color(switch.admin.to_string(), 4)?
+    ));
+    let recovery = client
+        .recover_post_deadman_switch(&key_id)
+        .await
+        .context("Failed to recover GPG wallet")?;
+    pb.finish_with_message("Recovery completed".green().to_string());
+    record["amountWei"] = json!(recovery.amount.to_string());
+    record["amount"] = json!(wei_to_eth_auto(recovery.amount));
+    record["symbol"] = json!(network.native_symbol);
+    output::emit(with_tx(record, &client, recovery.tx_hash).await?);
+    say!(
+        "{} {}: {}",
+        "Recovered".blue().bold(),
+        format_native(recovery.amount, network).green(),
+        hex_color(recovery.tx_hash.to_string(), 4)?
+    );
+    if let Some(url) = network.tx_url(recovery.tx_hash) {
+        say!("\t{}", url.to_string().dimmed());
+    }
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use crate::commands::{connect_sender, get_key_id, print_simulation};
+use crate::output::{self, say};
+use crate::utils::hex_color;
+use anyhow::Result;
+use clap::ArgMatches;
+use colored::Colorize;
+use libtea_gpg_wallet::network::Network;
+use serde_json::json;
+
+pub(crate) async fn handle_deploy(sub_m: &ArgMatches, network: &Network) -> Result<()> {
+    let client = connect_sender(sub_m, network)?;
+    let key_id = get_key_id(sub_m).await?;
+    client.verify_chain_id().await?;
+    if sub_m.get_flag("dry_run") {
+        let simulation = client.simulate_ensure_deployed(&key_id).await?;
+        return print_simulation(&key_id, &simulation, network, None);
+    }
+    let prediction = client.ensure_deployed(&key_id).await?;
+    output::emit(json!({
+        "keyId": key_id,
+        "address": prediction.walletAddress,
+        "deployed": true,
+    }));
+    say!(
+        "{} {}:",
+        "Deployed address for key ID".blue().bold(),
+        hex_color(&key_id, 4)?
+    );
+    say!("\t{}", hex_color(prediction.walletAddress.to_string(), 4)?);
+    Ok(())
+}
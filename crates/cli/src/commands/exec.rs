@@ -0,0 +1,110 @@
+use crate::commands::{
+    connect_for_signing, get_key_id, get_paymaster_fee, relay, sign_with_key, signed_record,
+    with_tx,
+};
+use crate::output::{self, say};
+use crate::utils::hex_color;
+use anyhow::{Context, Result, anyhow};
+use clap::ArgMatches;
+use colored::Colorize;
+use libtea_gpg_wallet::{
+    client::EXECUTE_GAS_ESTIMATE,
+    exec::{ContractCall, format_value},
+    network::Network,
+    utils::decimal_to_wei_precise,
+};
+use serde_json::json;
+
+pub(crate) async fn handle_exec(sub_m: &ArgMatches, network: &Network) -> Result<()> {
+    let (client, relayer) = connect_for_signing(sub_m, network)?;
+    let key_id = get_key_id(sub_m).await?;
+    let target = sub_m
+        .get_one::<String>("target")
+        .context("Target address not provided")?;
+    let call = sub_m
+        .get_one::<String>("call")
+        .context("Function signature or calldata not provided")?;
+    let args: Vec<&String> = sub_m.get_many("args").unwrap_or_default().collect();
+    let call = if call.starts_with("0x") {
+        if !args.is_empty() {
+            return Err(anyhow!("Arguments can't be combined with raw calldata"));
+        }
+        ContractCall::from_calldata(call, sub_m.get_one::<String>("returns").map(|s| s.as_str()))?
+    } else {
+        ContractCall::from_signature(call, &args)?
+    };
+    let value = decimal_to_wei_precise(
+        sub_m
+            .get_one::<String>("value")
+            .context("Value not provided")?,
+    )?;
+    client.verify_chain_id().await?;
+    let paymaster_fee = get_paymaster_fee(sub_m, &client, EXECUTE_GAS_ESTIMATE).await?;
+    let pb = output::spinner();
+    pb.set_message(format!(
+        "Calling {} from key ID {}",
+        hex_color(target, 4)?,
+        hex_color(&key_id, 4)?
+    ));
+    let signing_data = client
+        .get_execute_signable_hash(&key_id, target, value, call.data.clone(), paymaster_fee)
+        .await
+        .context("Failed to get signable hash")?;
+    let signature = sign_with_key(sub_m, &signing_data).await?;
+    // A relayer only reports the transaction hash, not the call's return data
+    let (tx, returndata) = match &relayer {
+        Some(relayer) => (
+            relay(&client, relayer, &key_id, &signing_data, &signature, &pb).await?,
+            None,
+        ),
+        None => {
+            let execution = client
+                .execute_with_sig(
+                    &key_id,
+                    &signing_data,
+                    &signature.public_key,
+                    &signature.signature,
+                )
+                .await
+                .context("Failed to execute call from GPG wallet")?;
+            (execution.tx_hash, Some(execution.returndata))
+        }
+    };
+    pb.finish_with_message("Call completed".green().to_string());
+    if !output::is_table() {
+        let mut record = signed_record(&key_id, &signing_data, network, relayer.is_some());
+        record["data"] = json!(signing_data.data);
+        let mut record = with_tx(record, &client, tx).await?;
+        let result = match &returndata {
+            Some(returndata) => call
+                .decode_output(returndata)?
+                .map(|values| values.iter().map(format_value).collect::<Vec<_>>()),
+            None => None,
+        };
+        record["returnData"] = json!(returndata);
+        record["result"] = json!(result);
+        output::emit(record);
+    }
+    say!(
+        "{} {}: {}",
+        "Call transaction hash for key ID".blue().bold(),
+        hex_color(&key_id, 4)?,
+        hex_color(tx.to_string(), 4)?
+    );
+    if let Some(url) = network.tx_url(tx) {
+        say!("\t{}", url.to_string().dimmed());
+    }
+    let Some(returndata) = returndata else {
+        return Ok(());
+    };
+    if let Some(values) = call.decode_output(&returndata)? {
+        say!("{}", "Result:".blue().bold());
+        for value in values {
+            say!("\t{}", format_value(&value).green());
+        }
+    } else if !returndata.is_empty() {
+        say!("{}", "Raw result:".blue().bold());
+        say!("\t{}", returndata.to_string().green());
+    }
+    Ok(())
+}
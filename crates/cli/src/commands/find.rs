@@ -0,0 +1,200 @@
+use crate::commands::get_key_ids;
+use crate::output::{self, say};
+use crate::utils::{format_native, hex_color, wei_to_eth_auto};
+use alloy::{
+    primitives::{Address, U256},
+    providers::Provider,
+};
+use anyhow::{Result, anyhow};
+use clap::ArgMatches;
+use colored::Colorize;
+use libtea_gpg_wallet::{
+    Error,
+    client::GpgWalletClient,
+    config::default_cache_path,
+    deployer::{ImplementationCache, predict_address_offline},
+    network::Network,
+};
+use serde_json::json;
+use std::path::PathBuf;
+
+pub(crate) async fn handle_find(sub_m: &ArgMatches, network: &Network) -> Result<()> {
+    let key_ids = get_key_ids(sub_m).await?;
+    if key_ids.len() > 1 || sub_m.get_one::<PathBuf>("file").is_some() {
+        return find_many(sub_m, network, &key_ids).await;
+    }
+    // Already resolved above; asking gpg or bpb again could prompt twice
+    let key_id = key_ids[0].clone();
+    let cache_path = default_cache_path();
+    let mut cache = match &cache_path {
+        Some(path) => ImplementationCache::load(path)?,
+        None => ImplementationCache::default(),
+    };
+    let client = GpgWalletClient::connect(network);
+    let prediction = if sub_m.get_flag("offline") {
+        None
+    } else {
+        match client.predict_address(&key_id).await {
+            Ok(prediction) => Some(prediction),
+            Err(Error::Transport(e)) if cache.get(network).is_some() => {
+                eprintln!("RPC endpoint unavailable ({e}), predicting offline");
+                None
+            }
+            Err(e) => return Err(e.into()),
+        }
+    };
+    let Some(prediction) = prediction else {
+        let implementation = cache.get(network).ok_or_else(|| {
+            anyhow!(
+                "No implementation address known for network {}; run find once online, or set implementation in the config file",
+                network.name
+            )
+        })?;
+        let address = predict_address_offline(network.deployer, implementation, &key_id)?;
+        output::emit(find_record(&key_id, address, None, network));
+        say!(
+            "{} {}:",
+            "Predicted address for key ID".blue().bold(),
+            hex_color(&key_id, 4)?
+        );
+        say!(
+            "\t{} ({})",
+            hex_color(address.to_string(), 4)?,
+            "offline, deployment unknown".dimmed()
+        );
+        return Ok(());
+    };
+    if !output::is_table() {
+        let balance = client
+            .provider()
+            .get_balance(prediction.walletAddress)
+            .await?;
+        output::emit(find_record(
+            &key_id,
+            prediction.walletAddress,
+            Some((prediction.isDeployed, balance)),
+            network,
+        ));
+    }
+    let deployed = if prediction.isDeployed {
+        "deployed".green().bold()
+    } else {
+        "not deployed".red().bold()
+    };
+    say!(
+        "{} {}:",
+        "Predicted address for key ID".blue().bold(),
+        hex_color(&key_id, 4)?
+    );
+    say!(
+        "\t{} ({deployed})",
+        hex_color(prediction.walletAddress.to_string(), 4)?
+    );
+    // Remember the implementation for the next offline prediction
+    if let Some(path) = cache_path
+        && cache.get(network).is_none()
+        && let Ok(implementation) = client.implementation().await
+    {
+        cache.insert(network, implementation);
+        cache.save(&path)?;
+    }
+    Ok(())
+}
+
+async fn find_many(sub_m: &ArgMatches, network: &Network, key_ids: &[String]) -> Result<()> {
+    if sub_m.get_flag("offline") {
+        let cache = match default_cache_path() {
+            Some(path) => ImplementationCache::load(&path)?,
+            None => ImplementationCache::default(),
+        };
+        let implementation = cache.get(network).ok_or_else(|| {
+            anyhow!(
+                "No implementation address known for network {}; run find once online, or set implementation in the config file",
+                network.name
+            )
+        })?;
+        say!(
+            "{} {} {}:",
+            "Predicted addresses for".blue().bold(),
+            key_ids.len(),
+            "key IDs".blue().bold()
+        );
+        let mut rows = Vec::with_capacity(key_ids.len());
+        for key_id in key_ids {
+            let address = predict_address_offline(network.deployer, implementation, key_id)?;
+            rows.push(find_record(key_id, address, None, network));
+            say!(
+                "\t{} {}",
+                hex_color(key_id, 4)?,
+                hex_color(address.to_string(), 4)?
+            );
+        }
+        output::emit_rows(&rows);
+        return Ok(());
+    }
+
+    let pb = output::spinner();
+    pb.set_message(format!("Looking up {} key IDs", key_ids.len()));
+    let wallets = GpgWalletClient::connect(network)
+        .lookup_key_ids(key_ids)
+        .await?;
+    pb.finish_and_clear();
+    output::emit_rows(
+        &wallets
+            .iter()
+            .map(|wallet| {
+                find_record(
+                    &wallet.key_id,
+                    wallet.address,
+                    Some((wallet.is_deployed, wallet.balance)),
+                    network,
+                )
+            })
+            .collect::<Vec<_>>(),
+    );
+    say!(
+        "{} {} {}:",
+        "Predicted addresses for".blue().bold(),
+        key_ids.len(),
+        "key IDs".blue().bold()
+    );
+    for wallet in &wallets {
+        let deployed = if wallet.is_deployed {
+            "deployed".green().bold()
+        } else {
+            "not deployed".red().bold()
+        };
+        say!(
+            "\t{} {} ({deployed}) {}",
+            hex_color(&wallet.key_id, 4)?,
+            hex_color(wallet.address.to_string(), 4)?,
+            format_native(wallet.balance, network).green()
+        );
+    }
+    let total = wallets.iter().map(|wallet| wallet.balance).sum::<U256>();
+    say!(
+        "{}: {}",
+        "Total balance".blue().bold(),
+        format_native(total, network).green()
+    );
+    Ok(())
+}
+
+// One wallet of find's output; `lookup` is whether it's deployed and its
+// balance, or None when predicted offline
+fn find_record(
+    key_id: &str,
+    address: Address,
+    lookup: Option<(bool, U256)>,
+    network: &Network,
+) -> serde_json::Value {
+    json!({
+        "keyId": key_id,
+        "address": address,
+        "deployed": lookup.map(|(deployed, _)| deployed),
+        "offline": lookup.is_none(),
+        "balanceWei": lookup.map(|(_, balance)| balance.to_string()),
+        "balance": lookup.map(|(_, balance)| wei_to_eth_auto(balance)),
+        "symbol": network.native_symbol,
+    })
+}
@@ -0,0 +1,170 @@
+use crate::commands::{get_key_id, tx_link};
+use crate::output::{self, say};
+use crate::utils::{format_native, format_unix_time, hex_color, wei_to_eth_auto};
+use alloy::{primitives::Address, providers::Provider};
+use anyhow::{Result, anyhow};
+use clap::ArgMatches;
+use colored::Colorize;
+use libtea_gpg_wallet::{
+    client::GpgWalletClient,
+    history::{HistoryCheckpoint, HistoryEntry},
+    network::Network,
+};
+use serde_json::json;
+use std::path::PathBuf;
+
+// Blocks scanned between checkpoint saves
+const HISTORY_CHUNK_BLOCKS: u64 = 1000;
+
+pub(crate) async fn handle_history(sub_m: &ArgMatches, network: &Network) -> Result<()> {
+    let checkpoint_path = sub_m.get_one::<PathBuf>("checkpoint");
+    if let Some(path) = checkpoint_path.filter(|path| path.exists())
+        && sub_m.contains_id("from_block")
+    {
+        return Err(anyhow!(
+            "Checkpoint {} already sets where the scan resumes; drop --from-block or remove the checkpoint",
+            path.display()
+        ));
+    }
+    let key_id = get_key_id(sub_m).await?;
+    let client = GpgWalletClient::connect(network);
+    client.verify_chain_id().await?;
+    let wallet = client.predict_address(&key_id).await?.walletAddress;
+    let to_block = match sub_m.get_one::<u64>("to_block") {
+        Some(block) => *block,
+        None => client.provider().get_block_number().await?,
+    };
+
+    let pb = output::spinner();
+    let mut checkpoint = match checkpoint_path.filter(|path| path.exists()) {
+        Some(path) => {
+            let checkpoint = HistoryCheckpoint::load(path)?;
+            if checkpoint.chain_id != network.chain_id || checkpoint.wallet != wallet {
+                return Err(anyhow!(
+                    "Checkpoint {} is for wallet {} on chain {}, not {wallet} on chain {}",
+                    path.display(),
+                    checkpoint.wallet,
+                    checkpoint.chain_id,
+                    network.chain_id
+                ));
+            }
+            checkpoint
+        }
+        None => {
+            let from_block = match sub_m.get_one::<u64>("from_block") {
+                Some(block) => *block,
+                None => {
+                    pb.set_message("Finding the wallet's first activity");
+                    // Nothing to scan yet, so start past the end
+                    client
+                        .first_active_block(wallet)
+                        .await?
+                        .unwrap_or(to_block + 1)
+                }
+            };
+            HistoryCheckpoint::new(network.chain_id, wallet, from_block)
+        }
+    };
+
+    while checkpoint.next_block <= to_block {
+        let end = (checkpoint.next_block + HISTORY_CHUNK_BLOCKS - 1).min(to_block);
+        pb.set_message(format!(
+            "Scanning blocks {}-{end} of {to_block}",
+            checkpoint.next_block
+        ));
+        let entries = client.history(wallet, checkpoint.next_block, end).await?;
+        checkpoint.entries.extend(entries);
+        checkpoint.next_block = end + 1;
+        if let Some(path) = checkpoint_path {
+            checkpoint.save(path)?;
+        }
+    }
+    pb.finish_and_clear();
+
+    print_history_table(&key_id, wallet, &checkpoint.entries, network)?;
+    output::emit_rows(
+        &checkpoint
+            .entries
+            .iter()
+            .map(|entry| history_entry_json(entry, network))
+            .collect::<Vec<_>>(),
+    );
+    Ok(())
+}
+
+fn print_history_table(
+    key_id: &str,
+    wallet: Address,
+    entries: &[HistoryEntry],
+    network: &Network,
+) -> Result<()> {
+    say!(
+        "{} {} ({}):",
+        "History for key ID".blue().bold(),
+        hex_color(key_id, 4)?,
+        hex_color(wallet.to_string(), 4)?
+    );
+    if entries.is_empty() {
+        say!("\t{}", "No transactions found".yellow());
+    }
+    for entry in entries {
+        say!(
+            "\t{} {} {}",
+            entry.block.to_string().blue(),
+            format_unix_time(entry.timestamp).dimmed(),
+            describe_history_entry(entry, wallet, network)?
+        );
+        say!("\t\t{}", tx_link(entry.tx_hash, network).dimmed());
+    }
+    Ok(())
+}
+
+// e.g. "withdrawAll    1.49 TEA, fee 0.01 TEA, nonce 3 to 0x..."
+pub(crate) fn describe_history_entry(
+    entry: &HistoryEntry,
+    wallet: Address,
+    network: &Network,
+) -> Result<String> {
+    let kind = format!("{:<14}", entry.kind.to_string());
+    let kind = if entry.success {
+        kind.normal()
+    } else {
+        format!("{kind} (reverted)").red()
+    };
+    let mut details = format!("{}", format_native(entry.amount, network).green());
+    if !entry.paymaster_fee.is_zero() {
+        details.push_str(&format!(
+            ", fee {}",
+            format_native(entry.paymaster_fee, network)
+        ));
+    }
+    if let Some(nonce) = entry.nonce {
+        details.push_str(&format!(", nonce {nonce}"));
+    }
+    let counterparty = if entry.to == wallet {
+        format!("from {}", hex_color(entry.from.to_string(), 4)?)
+    } else {
+        format!("to {}", hex_color(entry.to.to_string(), 4)?)
+    };
+    Ok(format!("{kind} {details} {counterparty}"))
+}
+
+pub(crate) fn history_entry_json(entry: &HistoryEntry, network: &Network) -> serde_json::Value {
+    json!({
+        "block": entry.block,
+        "timestamp": entry.timestamp,
+        "time": format_unix_time(entry.timestamp),
+        "txHash": entry.tx_hash,
+        "kind": entry.kind,
+        "from": entry.from,
+        "to": entry.to,
+        "amountWei": entry.amount.to_string(),
+        "amount": wei_to_eth_auto(entry.amount),
+        "paymasterFeeWei": entry.paymaster_fee.to_string(),
+        "paymasterFee": wei_to_eth_auto(entry.paymaster_fee),
+        "symbol": network.native_symbol,
+        "nonce": entry.nonce.map(|nonce| nonce.to_string()),
+        "data": entry.data,
+        "success": entry.success,
+    })
+}
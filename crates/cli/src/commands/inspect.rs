@@ -0,0 +1,118 @@
+use crate::commands::get_key_id;
+use crate::output::{self, say};
+use crate::utils::{format_duration, format_native, format_unix_time, hex_color, wei_to_eth_auto};
+use alloy::hex;
+use anyhow::Result;
+use clap::ArgMatches;
+use colored::Colorize;
+use libtea_gpg_wallet::{client::GpgWalletClient, network::Network, utils::unix_now};
+use serde_json::json;
+
+pub(crate) async fn handle_inspect(sub_m: &ArgMatches, network: &Network) -> Result<()> {
+    let key_id = get_key_id(sub_m).await?;
+    let client = GpgWalletClient::connect(network);
+    client.verify_chain_id().await?;
+    let info = client.inspect(&key_id).await?;
+    let now = unix_now();
+    let deadman = if info.deadman_switch.is_triggered(now) {
+        "fired".red().bold()
+    } else {
+        format!(
+            "fires in {}",
+            format_duration(info.deadman_switch.remaining(now))
+        )
+        .green()
+    };
+    let domain = &info.domain;
+    output::emit(json!({
+        "keyId": key_id,
+        "address": info.address,
+        "deployed": true,
+        "balanceWei": info.balance.to_string(),
+        "balance": wei_to_eth_auto(info.balance),
+        "symbol": network.native_symbol,
+        "admin": info.deadman_switch.admin,
+        "triggerDate": info.deadman_switch.trigger_date,
+        "fired": info.deadman_switch.is_triggered(now),
+        "remainingSecs": info.deadman_switch.remaining(now),
+        "nextNonce": info.next_nonce.to_string(),
+        "implementation": info.implementation,
+        "deployerImplementation": info.deployer_implementation,
+        "gpgVerifier": info.gpg_verifier,
+        "withdrawAllTypehash": info.withdraw_all_typehash,
+        "executeTypehash": info.execute_typehash,
+        "domain": {
+            "name": domain.name,
+            "version": domain.version,
+            "chainId": domain.chain_id.map(|id| id.to_string()),
+            "verifyingContract": domain.verifying_contract,
+        },
+        "mismatches": info
+            .mismatches(network)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+    }));
+    say!(
+        "{} {}:",
+        "Wallet for key ID".blue().bold(),
+        hex_color(&key_id, 4)?
+    );
+    let fields = [
+        ("Address", hex_color(info.address.to_string(), 4)?),
+        ("Balance", format_native(info.balance, network).green()),
+        ("Key ID", hex_color(hex::encode_upper(info.key_id), 4)?),
+        (
+            "Admin",
+            hex_color(info.deadman_switch.admin.to_string(), 4)?,
+        ),
+        (
+            "Dead man switch",
+            format!(
+                "{} ({deadman})",
+                format_unix_time(info.deadman_switch.trigger_date)
+            )
+            .normal(),
+        ),
+        ("Next nonce", info.next_nonce.to_string().normal()),
+        (
+            "Implementation",
+            hex_color(info.implementation.to_string(), 4)?,
+        ),
+        (
+            "Deployer implementation",
+            hex_color(info.deployer_implementation.to_string(), 4)?,
+        ),
+        ("GPG verifier", hex_color(info.gpg_verifier.to_string(), 4)?),
+        (
+            "WITHDRAW_ALL_TYPEHASH",
+            info.withdraw_all_typehash.to_string().dimmed(),
+        ),
+        (
+            "EXECUTE_TYPEHASH",
+            info.execute_typehash.to_string().dimmed(),
+        ),
+        (
+            "EIP-712 domain",
+            format!(
+                "{} v{}, chain {}, {}",
+                domain.name.as_deref().unwrap_or("-"),
+                domain.version.as_deref().unwrap_or("-"),
+                domain
+                    .chain_id
+                    .map_or_else(|| "-".to_string(), |id| id.to_string()),
+                domain
+                    .verifying_contract
+                    .map_or_else(|| "-".to_string(), |address| address.to_string()),
+            )
+            .normal(),
+        ),
+    ];
+    for (label, value) in fields {
+        say!("\t{}: {value}", label.blue().bold());
+    }
+    for mismatch in info.mismatches(network) {
+        say!("{}: {mismatch}", "Mismatch".red().bold());
+    }
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use crate::output::{self, say};
+use crate::utils::{format_native, hex_color, wei_to_eth_auto};
+use crate::{bpb, gpg};
+use alloy::providers::Provider;
+use anyhow::{Context, Result};
+use clap::ArgMatches;
+use colored::Colorize;
+use libtea_gpg_wallet::{client::GpgWalletClient, network::Network};
+use serde_json::json;
+
+pub(crate) async fn handle_list(sub_m: &ArgMatches, network: &Network) -> Result<()> {
+    let mut keys = if sub_m.get_flag("bpb") {
+        vec![gpg::GpgKey {
+            key_id: bpb::get_key_id().await?,
+            uid: None,
+            secret: true,
+        }]
+    } else {
+        gpg::list_keys().await?
+    };
+    if sub_m.get_flag("secret") {
+        keys.retain(|key| key.secret);
+    }
+    if let Some(filter) = sub_m.get_one::<String>("uid") {
+        let filter = filter.to_lowercase();
+        keys.retain(|key| {
+            key.uid
+                .as_ref()
+                .is_some_and(|uid| uid.to_lowercase().contains(&filter))
+        });
+    }
+    if keys.is_empty() {
+        eprintln!("No matching keys found");
+        return Ok(());
+    }
+
+    // One lookup per key, all at once
+    let client = GpgWalletClient::connect(network);
+    let mut lookups = tokio::task::JoinSet::new();
+    for (i, key) in keys.iter().enumerate() {
+        let client = client.clone();
+        let key_id = key.key_id.clone();
+        lookups.spawn(async move {
+            let prediction = client.predict_address(&key_id).await?;
+            let balance = if prediction.isDeployed {
+                Some(
+                    client
+                        .provider()
+                        .get_balance(prediction.walletAddress)
+                        .await?,
+                )
+            } else {
+                None
+            };
+            Ok::<_, libtea_gpg_wallet::Error>((i, prediction.walletAddress, balance))
+        });
+    }
+    let mut wallets = vec![None; keys.len()];
+    while let Some(lookup) = lookups.join_next().await {
+        let (i, address, balance) = lookup??;
+        wallets[i] = Some((address, balance));
+    }
+
+    let mut rows = Vec::with_capacity(keys.len());
+    for (key, wallet) in keys.iter().zip(wallets) {
+        let (address, balance) = wallet.context("Missing wallet lookup")?;
+        rows.push(json!({
+            "keyId": key.key_id,
+            "uid": key.uid,
+            "secret": key.secret,
+            "address": address,
+            "deployed": balance.is_some(),
+            "balanceWei": balance.map(|balance| balance.to_string()),
+            "balance": balance.map(wei_to_eth_auto),
+            "symbol": network.native_symbol,
+        }));
+        let owned = if key.secret { "sec" } else { "pub" };
+        say!(
+            "{} {} {}",
+            hex_color(&key.key_id, 4)?,
+            owned.dimmed(),
+            key.uid.as_deref().unwrap_or("").bold()
+        );
+        let status = match balance {
+            Some(balance) => format_native(balance, network).green(),
+            None => "not deployed".red().bold(),
+        };
+        say!("\t{} ({status})", hex_color(address.to_string(), 4)?);
+    }
+    output::emit_rows(&rows);
+    Ok(())
+}
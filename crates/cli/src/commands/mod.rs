@@ -0,0 +1,414 @@
+pub(crate) mod config;
+pub(crate) mod deadman;
+pub(crate) mod deploy;
+pub(crate) mod exec;
+pub(crate) mod find;
+pub(crate) mod history;
+pub(crate) mod inspect;
+pub(crate) mod list;
+pub(crate) mod request;
+pub(crate) mod send;
+pub(crate) mod sweep;
+pub(crate) mod token;
+pub(crate) mod watch;
+pub(crate) mod withdraw;
+
+#[cfg(feature = "openpgp")]
+use crate::keyfile;
+use crate::output::{self, say};
+use crate::utils::{
+    ETH_DECIMALS, SigningResult, filter_hex_string, format_native, hex_color, require_private_key,
+    wei_to_eth_auto,
+};
+use crate::{bpb, gpg};
+use alloy::{
+    primitives::{TxHash, U256},
+    providers::{DynProvider, Provider},
+};
+use anyhow::{Context, Result, anyhow};
+use clap::ArgMatches;
+use colored::Colorize;
+use indicatif::ProgressBar;
+use libtea_gpg_wallet::{
+    client::GpgWalletClient,
+    network::Network,
+    relay::{RelayRequest, RelayerClient, TxStatus},
+    simulate::Simulation,
+    token::TokenInfo,
+    tx::TxOptions,
+    utils::{decimal_to_units, decimal_to_wei_precise, units_to_decimal},
+    wallet::SigningData,
+};
+use serde_json::json;
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+const RELAY_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const RELAY_TIMEOUT: Duration = Duration::from_secs(300);
+
+pub(crate) async fn get_key_id(sub_m: &ArgMatches) -> Result<String> {
+    if sub_m.try_get_one::<String>("key_id").is_ok() && sub_m.get_one::<String>("key_id").is_some()
+    {
+        sub_m
+            .get_one::<String>("key_id")
+            .map(|s| filter_hex_string(s))
+            .context("Key ID not valid")
+    } else if sub_m.get_flag("bpb") {
+        bpb::get_key_id()
+            .await
+            .context("Failed to get key ID from bpb")
+    } else if sub_m.get_one::<String>("gpg").is_some() {
+        let email = sub_m
+            .get_one::<String>("gpg")
+            .context("Email address not provided")?;
+        gpg::get_key_id(email)
+            .await
+            .context("Failed to get key ID from gpg")
+    } else {
+        #[cfg(feature = "openpgp")]
+        if let Some(path) = sub_m.get_one::<PathBuf>("key_file") {
+            return keyfile::get_key_id(path).await;
+        }
+        Err(anyhow!("No key ID provided"))
+    }
+}
+
+// Every key ID given as arguments or in --file, or else the one key source
+pub(crate) async fn get_key_ids(sub_m: &ArgMatches) -> Result<Vec<String>> {
+    let mut key_ids: Vec<String> = sub_m
+        .get_many::<String>("key_id")
+        .map(|ids| ids.map(|id| filter_hex_string(id)).collect())
+        .unwrap_or_default();
+    if let Some(path) = sub_m.get_one::<PathBuf>("file") {
+        key_ids.extend(read_key_ids(path)?);
+    } else if key_ids.is_empty() {
+        key_ids.push(get_key_id(sub_m).await?);
+    }
+    Ok(key_ids)
+}
+
+// One key ID per line; blank lines and '#' comments are skipped
+fn read_key_ids(path: &Path) -> Result<Vec<String>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read key IDs from {}", path.display()))?;
+    Ok(contents
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty())
+        .map(filter_hex_string)
+        .collect())
+}
+
+pub(crate) async fn sign_with_key(sub_m: &ArgMatches, blob: &SigningData) -> Result<SigningResult> {
+    if sub_m.get_flag("bpb") {
+        return bpb::sign_blob(blob)
+            .await
+            .context("Failed to sign blob with bpb");
+    } else if sub_m.get_one::<String>("gpg").is_some() {
+        let key_id = get_key_id(sub_m).await?;
+        return gpg::sign_blob(blob, &key_id)
+            .await
+            .context("Failed to sign blob with gpg");
+    }
+    #[cfg(feature = "openpgp")]
+    if let Some(path) = sub_m.get_one::<PathBuf>("key_file") {
+        return keyfile::sign_blob(blob, path).await;
+    }
+    Err(anyhow!("No signing method provided"))
+}
+
+// Signed operations are sent from PRIVATE_KEY, or handed to a relayer with --relayer
+pub(crate) fn connect_for_signing(
+    sub_m: &ArgMatches,
+    network: &Network,
+) -> Result<(GpgWalletClient<DynProvider>, Option<RelayerClient>)> {
+    match sub_m.get_one::<String>("relayer") {
+        Some(url) => {
+            if tx_options(sub_m)? != TxOptions::default() {
+                return Err(anyhow!(
+                    "Gas and fee options don't apply to relayed transactions; the relayer sets its own"
+                ));
+            }
+            Ok((
+                GpgWalletClient::connect(network),
+                Some(RelayerClient::new(url)?),
+            ))
+        }
+        None => Ok((connect_sender(sub_m, network)?, None)),
+    }
+}
+
+// A client that sends from PRIVATE_KEY, with the gas and fee options applied
+pub(crate) fn connect_sender(
+    sub_m: &ArgMatches,
+    network: &Network,
+) -> Result<GpgWalletClient<DynProvider>> {
+    Ok(
+        GpgWalletClient::connect_with_signer(network, &require_private_key()?)?
+            .with_tx_options(tx_options(sub_m)?),
+    )
+}
+
+pub(crate) fn tx_options(sub_m: &ArgMatches) -> Result<TxOptions> {
+    let gwei = |id: &str| -> Result<Option<u128>> {
+        sub_m
+            .get_one::<String>(id)
+            .map(|value| {
+                decimal_to_units(value, 9)?
+                    .try_into()
+                    .map_err(|_| anyhow!("{value} gwei is too large"))
+            })
+            .transpose()
+    };
+    Ok(TxOptions {
+        gas_limit: sub_m.get_one::<u64>("gas_limit").copied(),
+        max_fee_per_gas: gwei("max_fee_per_gas")?,
+        max_priority_fee_per_gas: gwei("max_priority_fee")?,
+        legacy: sub_m.get_flag("legacy"),
+        nonce: sub_m.get_one::<u64>("nonce").copied(),
+        max_total_fee: sub_m
+            .get_one::<String>("max_total_fee")
+            .map(|fee| decimal_to_wei_precise(fee))
+            .transpose()?,
+    })
+}
+
+// A relayer won't submit without a fee, so it defaults to a suggested one there
+pub(crate) async fn get_paymaster_fee(
+    sub_m: &ArgMatches,
+    client: &GpgWalletClient<DynProvider>,
+    gas: u64,
+) -> Result<U256> {
+    let relayed = sub_m.get_one::<String>("relayer").is_some();
+    let fee = match sub_m.get_one::<String>("paymaster_fee").map(String::as_str) {
+        Some("auto") => client.suggest_paymaster_fee(gas).await?,
+        Some(fee) => decimal_to_wei_precise(fee)?,
+        None if relayed => client.suggest_paymaster_fee(gas).await?,
+        None => U256::ZERO,
+    };
+    if !fee.is_zero() {
+        say!(
+            "{}: {}",
+            "Paymaster fee".blue().bold(),
+            format_native(fee, client.network()).green()
+        );
+    }
+    Ok(fee)
+}
+
+// The signature is verified and the call simulated locally before anything is posted
+pub(crate) async fn relay(
+    client: &GpgWalletClient<DynProvider>,
+    relayer: &RelayerClient,
+    key_id: &str,
+    signing_data: &SigningData,
+    signature: &SigningResult,
+    pb: &ProgressBar,
+) -> Result<TxHash> {
+    let request = RelayRequest::new(
+        key_id,
+        signing_data,
+        &signature.public_key,
+        &signature.signature,
+    );
+    client
+        .check_relay(&request)
+        .await
+        .context("Refusing to relay the signed request")?;
+    let submission = relayer
+        .submit(&request)
+        .await
+        .with_context(|| format!("Relayer {} did not accept the request", relayer.url()))?;
+    pb.set_message(format!(
+        "Waiting for relayed transaction {}",
+        hex_color(submission.tx_hash.to_string(), 4)?
+    ));
+    match relayer
+        .wait_until_mined(submission.tx_hash, RELAY_POLL_INTERVAL, RELAY_TIMEOUT)
+        .await?
+    {
+        TxStatus::Reverted { .. } => Err(anyhow!(
+            "Relayed transaction {} reverted",
+            submission.tx_hash
+        )),
+        _ => Ok(submission.tx_hash),
+    }
+}
+
+// What a signed operation did, before its transaction fields
+pub(crate) fn signed_record(
+    key_id: &str,
+    signing_data: &SigningData,
+    network: &Network,
+    relayed: bool,
+) -> serde_json::Value {
+    json!({
+        "keyId": key_id,
+        "address": signing_data.wallet,
+        "operation": signing_data.operation,
+        "to": signing_data.to,
+        "valueWei": signing_data.value.to_string(),
+        "value": wei_to_eth_auto(signing_data.value),
+        "paymasterFeeWei": signing_data.paymaster_fee.to_string(),
+        "paymasterFee": wei_to_eth_auto(signing_data.paymaster_fee),
+        "symbol": network.native_symbol,
+        "nonce": signing_data.nonce.to_string(),
+        "relayed": relayed,
+    })
+}
+
+// Adds a mined transaction's hash, gas used and explorer link to `record`;
+// gas used costs a receipt lookup, so tables skip it
+pub(crate) async fn with_tx(
+    mut record: serde_json::Value,
+    client: &GpgWalletClient<DynProvider>,
+    tx: TxHash,
+) -> Result<serde_json::Value> {
+    let gas_used = if output::is_table() {
+        None
+    } else {
+        client
+            .provider()
+            .get_transaction_receipt(tx)
+            .await?
+            .map(|receipt| receipt.gas_used)
+    };
+    record["txHash"] = json!(tx);
+    record["gasUsed"] = json!(gas_used);
+    record["explorerUrl"] = json!(client.network().tx_url(tx).map(|url| url.to_string()));
+    Ok(record)
+}
+
+// The explorer link for a transaction, or just its hash
+pub(crate) fn tx_link(tx_hash: TxHash, network: &Network) -> String {
+    network
+        .tx_url(tx_hash)
+        .map_or_else(|| tx_hash.to_string(), |url| url.to_string())
+}
+
+// What --dry-run found; balances are of `token` if given, otherwise native
+pub(crate) fn print_simulation(
+    key_id: &str,
+    simulation: &Simulation,
+    network: &Network,
+    token: Option<&TokenInfo>,
+) -> Result<()> {
+    let format_balance = |amount: U256| match token {
+        Some(token) => token.format(amount),
+        None => format_native(amount, network),
+    };
+    let decimals = token.map_or(ETH_DECIMALS, |token| token.decimals as usize);
+    let gwei = |wei: u128| units_to_decimal(U256::from(wei), 9);
+    output::emit(json!({
+        "dryRun": true,
+        "keyId": key_id,
+        "address": simulation.wallet,
+        "deployed": simulation.was_deployed,
+        "transactions": simulation
+            .transactions
+            .iter()
+            .map(|tx| json!({
+                "from": tx.request.from,
+                "to": tx.request.to.and_then(|to| to.to().copied()),
+                "valueWei": tx.value().to_string(),
+                "value": wei_to_eth_auto(tx.value()),
+                "data": tx.request.input.input(),
+                "nonce": tx.request.nonce,
+                "gasLimit": tx.fee.gas_limit,
+                "maxFeePerGasWei": tx.fee.max_fee_per_gas.to_string(),
+                "maxPriorityFeePerGasWei": tx.fee.max_priority_fee_per_gas.map(|fee| fee.to_string()),
+                "legacy": tx.fee.max_priority_fee_per_gas.is_none(),
+                "maxFeeWei": tx.fee.total().to_string(),
+                "maxFee": wei_to_eth_auto(tx.fee.total()),
+            }))
+            .collect::<Vec<_>>(),
+        "totalFeeWei": simulation.total_fee().to_string(),
+        "totalFee": wei_to_eth_auto(simulation.total_fee()),
+        "totalCostWei": simulation.total_cost().to_string(),
+        "totalCost": wei_to_eth_auto(simulation.total_cost()),
+        "nativeSymbol": network.native_symbol,
+        "balanceRaw": simulation.balance.to_string(),
+        "balance": units_to_decimal(simulation.balance, decimals),
+        "newBalanceRaw": simulation.new_balance.to_string(),
+        "newBalance": units_to_decimal(simulation.new_balance, decimals),
+        "symbol": token.map_or(network.native_symbol.as_str(), |token| token.symbol.as_str()),
+    }));
+
+    say!(
+        "{} {} ({})",
+        "Dry run for key ID".blue().bold(),
+        hex_color(key_id, 4)?,
+        "nothing was sent".yellow()
+    );
+    let deployed = if simulation.was_deployed {
+        "deployed".green().bold()
+    } else {
+        "not deployed".red().bold()
+    };
+    say!(
+        "\t{}: {} ({deployed})",
+        "Wallet".blue().bold(),
+        hex_color(simulation.wallet.to_string(), 4)?
+    );
+    if simulation.transactions.is_empty() {
+        say!("\t{}", "No transactions needed".green());
+    }
+    for (i, tx) in simulation.transactions.iter().enumerate() {
+        say!("{} {}:", "Transaction".blue().bold(), i + 1);
+        let mut rows = vec![
+            (
+                "From",
+                tx.request
+                    .from
+                    .map_or_else(|| "-".to_string(), |from| from.to_string()),
+            ),
+            (
+                "To",
+                tx.request
+                    .to
+                    .and_then(|to| to.to().copied())
+                    .map_or_else(|| "-".to_string(), |to| to.to_string()),
+            ),
+            ("Value", format_native(tx.value(), network)),
+        ];
+        if let Some(data) = tx.request.input.input().filter(|data| !data.is_empty()) {
+            rows.push(("Data", data.to_string()));
+        }
+        if let Some(nonce) = tx.request.nonce {
+            rows.push(("Nonce", nonce.to_string()));
+        }
+        rows.push(("Gas limit", tx.fee.gas_limit.to_string()));
+        match tx.fee.max_priority_fee_per_gas {
+            Some(priority_fee) => {
+                rows.push((
+                    "Max fee per gas",
+                    format!("{} gwei", gwei(tx.fee.max_fee_per_gas)),
+                ));
+                rows.push(("Priority fee", format!("{} gwei", gwei(priority_fee))));
+            }
+            None => rows.push((
+                "Gas price",
+                format!("{} gwei (legacy)", gwei(tx.fee.max_fee_per_gas)),
+            )),
+        }
+        rows.push(("Max fee", format_native(tx.fee.total(), network)));
+        for (label, value) in rows {
+            say!("\t{}: {}", label.blue().bold(), value);
+        }
+    }
+    say!(
+        "{}: {} ({} in gas)",
+        "Total cost".blue().bold(),
+        format_native(simulation.total_cost(), network).green(),
+        format_native(simulation.total_fee(), network)
+    );
+    say!(
+        "{}: {} -> {}",
+        "Wallet balance".blue().bold(),
+        format_balance(simulation.balance),
+        format_balance(simulation.new_balance).green()
+    );
+    Ok(())
+}
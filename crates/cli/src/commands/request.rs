@@ -0,0 +1,174 @@
+use crate::commands::{connect_for_signing, relay, signed_record, with_tx};
+use crate::gpg;
+#[cfg(feature = "openpgp")]
+use crate::keyfile;
+use crate::output::{self, say};
+use crate::utils::{SigningResult, format_native, hex_color, wei_to_eth_auto};
+use alloy::{primitives::U256, providers::Provider};
+use anyhow::{Context, Result, anyhow};
+use clap::ArgMatches;
+use colored::Colorize;
+use libtea_gpg_wallet::{
+    config::Config, network::Network, request::SigningRequest, utils::unix_now,
+};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+
+pub(crate) async fn handle_sign_request(sub_m: &ArgMatches, config: &Config) -> Result<()> {
+    let path = sub_m
+        .get_one::<PathBuf>("file")
+        .context("Signing request file not provided")?;
+    let mut request = SigningRequest::load(path)?;
+    describe_request(&request, config.networks.get(&request.network));
+    if request.deadline < U256::from(unix_now()) {
+        return Err(anyhow!("Signing request expired; prepare a new one"));
+    }
+    request
+        .check_struct_hash()
+        .context("Refusing to sign: the struct hash doesn't match the request")?;
+    let signature = sign_request(sub_m, &request).await?;
+    request.public_key = Some(signature.public_key);
+    request.signature = Some(signature.signature);
+    let out = sub_m.get_one::<PathBuf>("out").unwrap_or(path);
+    request.save(out)?;
+    output::emit(request_record(&request, out));
+    say!(
+        "{} {}",
+        "Signed request written to".blue().bold(),
+        out.display().to_string().green()
+    );
+    Ok(())
+}
+
+async fn sign_request(sub_m: &ArgMatches, request: &SigningRequest) -> Result<SigningResult> {
+    #[cfg(feature = "openpgp")]
+    if let Some(path) = sub_m.get_one::<PathBuf>("key_file") {
+        let key_id = keyfile::get_key_id(path).await?;
+        if !key_id.eq_ignore_ascii_case(&request.key_id) {
+            return Err(anyhow!(
+                "Key file is for key ID {key_id}, but the request is for {}",
+                request.key_id
+            ));
+        }
+        return keyfile::sign_blob(&request.signing_data(), path).await;
+    }
+    #[cfg(not(feature = "openpgp"))]
+    let _ = sub_m;
+    gpg::sign_blob(&request.signing_data(), &request.key_id)
+        .await
+        .context("Failed to sign request with gpg")
+}
+
+pub(crate) async fn handle_submit(sub_m: &ArgMatches, network: &Network) -> Result<()> {
+    let path = sub_m
+        .get_one::<PathBuf>("file")
+        .context("Signed request file not provided")?;
+    let request = SigningRequest::load(path)?;
+    let (public_key, signature) = request.signed()?;
+    let (client, relayer) = connect_for_signing(sub_m, network)?;
+    client.verify_chain_id().await?;
+    describe_request(&request, Some(network));
+    let pb = output::spinner();
+    pb.set_message(format!(
+        "Submitting signed request for key ID {}",
+        hex_color(&request.key_id, 4)?
+    ));
+    let tx = match &relayer {
+        Some(relayer) => {
+            let signature = SigningResult {
+                signature: signature.to_string(),
+                public_key: public_key.to_string(),
+            };
+            relay(
+                &client,
+                relayer,
+                &request.key_id,
+                &request.signing_data(),
+                &signature,
+                &pb,
+            )
+            .await?
+        }
+        None => client
+            .submit_signing_request(&request)
+            .await
+            .context("Failed to submit signed request")?,
+    };
+    pb.finish_with_message("Submission completed".green().to_string());
+    let new_balance = client.provider().get_balance(request.wallet).await?;
+    let record = signed_record(
+        &request.key_id,
+        &request.signing_data(),
+        network,
+        relayer.is_some(),
+    );
+    let mut record = with_tx(record, &client, tx).await?;
+    record["balanceWei"] = json!(new_balance.to_string());
+    record["balance"] = json!(wei_to_eth_auto(new_balance));
+    output::emit(record);
+    say!(
+        "{} {}: {}",
+        "Transaction hash for key ID".blue().bold(),
+        hex_color(&request.key_id, 4)?,
+        hex_color(tx.to_string(), 4)?
+    );
+    if let Some(url) = network.tx_url(tx) {
+        say!("\t{}", url.to_string().dimmed());
+    }
+    say!(
+        "{} {}: {}",
+        "New balance for key ID".blue().bold(),
+        hex_color(&request.key_id, 4)?,
+        format_native(new_balance, network).green()
+    );
+    Ok(())
+}
+
+// A signing request's summary, without its typed data or signature
+pub(crate) fn request_record(request: &SigningRequest, path: &Path) -> serde_json::Value {
+    json!({
+        "file": path.display().to_string(),
+        "network": request.network,
+        "chainId": request.chain_id,
+        "keyId": request.key_id,
+        "address": request.wallet,
+        "operation": request.operation,
+        "to": request.to,
+        "valueWei": request.value.to_string(),
+        "paymasterFeeWei": request.paymaster_fee.to_string(),
+        "nonce": request.nonce.to_string(),
+        "deadline": request.deadline.to_string(),
+        "structHash": request.struct_hash,
+        "data": request.data,
+        "signed": request.signature.is_some(),
+    })
+}
+
+// Shows what a request commits to, so it can be checked before signing
+pub(crate) fn describe_request(request: &SigningRequest, network: Option<&Network>) {
+    let amount = |wei: U256| match network {
+        Some(network) => format_native(wei, network),
+        None => format!("{wei} wei"),
+    };
+    let rows = [
+        (
+            "Network",
+            format!("{} (chain {})", request.network, request.chain_id),
+        ),
+        ("Wallet", request.wallet.to_string()),
+        ("Key ID", request.key_id.clone()),
+        ("Operation", format!("{:?}", request.operation)),
+        ("Destination", request.to.to_string()),
+        ("Value", amount(request.value)),
+        ("Paymaster fee", amount(request.paymaster_fee)),
+        ("Nonce", request.nonce.to_string()),
+        ("Deadline", format!("{} (unix time)", request.deadline)),
+        ("Struct hash", request.struct_hash.to_string()),
+    ];
+    for (label, value) in rows {
+        say!("{:>14}: {}", label.blue().bold(), value.green());
+    }
+    if !request.data.is_empty() {
+        say!("{:>14}: {}", "Calldata".blue().bold(), request.data);
+    }
+}
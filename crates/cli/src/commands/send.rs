@@ -0,0 +1,131 @@
+use crate::commands::{connect_sender, get_key_id, print_simulation, with_tx};
+use crate::output::{self, say};
+use crate::utils::{format_native, hex_color, wei_to_eth_auto};
+use alloy::providers::DynProvider;
+use anyhow::{Context, Result};
+use clap::ArgMatches;
+use colored::Colorize;
+use libtea_gpg_wallet::{
+    client::GpgWalletClient,
+    network::Network,
+    utils::{decimal_to_units, decimal_to_wei_precise, units_to_decimal},
+};
+use serde_json::json;
+
+pub(crate) async fn handle_send(sub_m: &ArgMatches, network: &Network) -> Result<()> {
+    let client = connect_sender(sub_m, network)?;
+    let key_id = get_key_id(sub_m).await?;
+    let amount_str = sub_m
+        .get_one::<String>("amount")
+        .context("Amount not provided")?;
+    client.verify_chain_id().await?;
+    if let Some(token) = sub_m.get_one::<String>("token") {
+        return send_token(
+            &client,
+            &key_id,
+            token,
+            amount_str,
+            sub_m.get_flag("dry_run"),
+        )
+        .await;
+    }
+    let amount = decimal_to_wei_precise(amount_str)?;
+    if sub_m.get_flag("dry_run") {
+        let simulation = client.simulate_send_to_gpg_key(&key_id, amount).await?;
+        return print_simulation(&key_id, &simulation, network, None);
+    }
+    if client.predict_address(&key_id).await?.isDeployed {
+        let balance = client.get_key_id_balance(&key_id).await?;
+        say!(
+            "{} {}: {}",
+            "Balance for key ID".blue().bold(),
+            hex_color(&key_id, 4)?,
+            format_native(balance, network).green()
+        );
+    }
+    let pb = output::spinner();
+    pb.set_message(format!(
+        "Sending {} to key ID {}",
+        format_native(amount, network).green(),
+        hex_color(&key_id, 4)?
+    ));
+    let transfer = client.send_to_gpg_key(&key_id, amount).await?;
+    pb.finish_with_message("Send completed".green().to_string());
+    let new_balance = transfer.new_balance;
+    let record = json!({
+        "keyId": key_id,
+        "address": transfer.wallet,
+        "amountWei": amount.to_string(),
+        "amount": wei_to_eth_auto(amount),
+        "symbol": network.native_symbol,
+    });
+    let mut record = with_tx(record, &client, transfer.tx_hash).await?;
+    record["balanceWei"] = json!(new_balance.to_string());
+    record["balance"] = json!(wei_to_eth_auto(new_balance));
+    output::emit(record);
+    say!(
+        "{} {}: {}",
+        "New balance for key ID".blue().bold(),
+        hex_color(&key_id, 4)?,
+        format_native(new_balance, network).green()
+    );
+    Ok(())
+}
+
+async fn send_token(
+    client: &GpgWalletClient<DynProvider>,
+    key_id: &str,
+    token: &str,
+    amount_str: &str,
+    dry_run: bool,
+) -> Result<()> {
+    let token = client.token_info(token).await?;
+    let amount = decimal_to_units(amount_str, token.decimals as usize)?;
+    if dry_run {
+        let simulation = client
+            .simulate_send_token_to_gpg_key(key_id, &token, amount)
+            .await?;
+        return print_simulation(key_id, &simulation, client.network(), Some(&token));
+    }
+    if client.predict_address(key_id).await?.isDeployed {
+        let balance = client.get_key_id_token_balance(key_id, &token).await?;
+        say!(
+            "{} {} {}: {}",
+            token.symbol.blue().bold(),
+            "balance for key ID".blue().bold(),
+            hex_color(key_id, 4)?,
+            token.format(balance).green()
+        );
+    }
+    let pb = output::spinner();
+    pb.set_message(format!(
+        "Sending {} to key ID {}",
+        token.format(amount).green(),
+        hex_color(key_id, 4)?
+    ));
+    let transfer = client.send_token_to_gpg_key(key_id, &token, amount).await?;
+    pb.finish_with_message("Send completed".green().to_string());
+    let new_balance = transfer.new_balance;
+    let record = json!({
+        "keyId": key_id,
+        "address": transfer.wallet,
+        "token": token.address,
+        "symbol": token.symbol,
+        "decimals": token.decimals,
+        "amountRaw": amount.to_string(),
+        "amount": units_to_decimal(amount, token.decimals as usize),
+    });
+    let mut record = with_tx(record, client, transfer.tx_hash).await?;
+    record["balanceRaw"] = json!(new_balance.to_string());
+    record["balance"] = json!(units_to_decimal(new_balance, token.decimals as usize));
+    output::emit(record);
+    say!(
+        "{} {} {} {}: {}",
+        "New".blue().bold(),
+        token.symbol.blue().bold(),
+        "balance for key ID".blue().bold(),
+        hex_color(key_id, 4)?,
+        token.format(new_balance).green()
+    );
+    Ok(())
+}
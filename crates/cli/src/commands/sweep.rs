@@ -0,0 +1,156 @@
+use crate::commands::request::{describe_request, request_record};
+use crate::commands::{
+    connect_for_signing, get_key_id, get_paymaster_fee, print_simulation, relay, sign_with_key,
+    signed_record, with_tx,
+};
+use crate::output::{self, say};
+use crate::utils::{filter_hex_string, format_native, hex_color, wei_to_eth_auto};
+use alloy::{primitives::U256, providers::Provider};
+use anyhow::{Context, Result, anyhow};
+use clap::ArgMatches;
+use colored::Colorize;
+use libtea_gpg_wallet::{
+    client::{GpgWalletClient, SWEEP_GAS_ESTIMATE},
+    network::Network,
+    utils::unix_now,
+};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+
+// Leaves time to carry a prepared request to an offline machine and back
+const PREPARED_REQUEST_TTL_SECS: u64 = 3600;
+
+pub(crate) async fn handle_sweep(sub_m: &ArgMatches, network: &Network) -> Result<()> {
+    if let Some(path) = sub_m.get_one::<PathBuf>("prepare") {
+        return prepare_sweep(sub_m, network, path).await;
+    }
+    let (client, relayer) = connect_for_signing(sub_m, network)?;
+    let key_id = get_key_id(sub_m).await?;
+    let to_address = sub_m
+        .get_one::<String>("destination")
+        .context("Destination address not provided")?;
+    let to_address = filter_hex_string(to_address.strip_prefix("0x").unwrap_or(to_address));
+    client.verify_chain_id().await?;
+    let balance = client.get_key_id_balance(&key_id).await?;
+    say!(
+        "{} {}: {}",
+        "Balance for key ID".blue().bold(),
+        hex_color(&key_id, 4)?,
+        format_native(balance, network).green()
+    );
+    if balance.is_zero() {
+        eprintln!("No balance to sweep for key ID {key_id}");
+        return Ok(());
+    }
+    let paymaster_fee = get_paymaster_fee(sub_m, &client, SWEEP_GAS_ESTIMATE).await?;
+    if paymaster_fee > balance {
+        return Err(anyhow!(
+            "Paymaster fee {} exceeds the wallet balance",
+            format_native(paymaster_fee, network)
+        ));
+    }
+    let pb = output::spinner();
+    pb.set_message(format!(
+        "Sweeping {} from key ID {} to 0x{}",
+        format_native(balance - paymaster_fee, network).green(),
+        hex_color(&key_id, 4)?,
+        to_address.green()
+    ));
+    let signing_data = client
+        .get_signable_hash(&key_id, &to_address, paymaster_fee)
+        .await
+        .context("Failed to get signable hash")?;
+    let signature = sign_with_key(sub_m, &signing_data).await?;
+    if sub_m.get_flag("dry_run") {
+        let simulation = client
+            .simulate_sweep_gpg_key(
+                &key_id,
+                &to_address,
+                signing_data.paymaster_fee,
+                signing_data.deadline,
+                &signature.public_key,
+                &signature.signature,
+            )
+            .await?;
+        pb.finish_and_clear();
+        return print_simulation(&key_id, &simulation, network, None);
+    }
+    let tx = match &relayer {
+        Some(relayer) => relay(&client, relayer, &key_id, &signing_data, &signature, &pb).await?,
+        None => client
+            .sweep_gpg_key(
+                &key_id,
+                &to_address,
+                signing_data.paymaster_fee,
+                signing_data.deadline,
+                &signature.public_key,
+                &signature.signature,
+            )
+            .await
+            .context("Failed to sweep GPG wallet")?,
+    };
+    pb.finish_with_message("Sweep completed".green().to_string());
+    let new_balance = client.provider().get_balance(signing_data.wallet).await?;
+    let mut record = signed_record(&key_id, &signing_data, network, relayer.is_some());
+    record["valueWei"] = json!((balance - paymaster_fee).to_string());
+    record["value"] = json!(wei_to_eth_auto(balance - paymaster_fee));
+    let mut record = with_tx(record, &client, tx).await?;
+    record["balanceWei"] = json!(new_balance.to_string());
+    record["balance"] = json!(wei_to_eth_auto(new_balance));
+    output::emit(record);
+    say!(
+        "{} {}: {}",
+        "Sweep transaction hash for key ID".blue().bold(),
+        hex_color(&key_id, 4)?,
+        hex_color(tx.to_string(), 4)?
+    );
+    if let Some(url) = network.tx_url(tx) {
+        say!("\t{}", url.to_string().dimmed());
+    }
+    say!(
+        "{} {}: {}",
+        "New balance for key ID".blue().bold(),
+        hex_color(&key_id, 4)?,
+        format_native(new_balance, network).green()
+    );
+    Ok(())
+}
+
+async fn prepare_sweep(sub_m: &ArgMatches, network: &Network, path: &Path) -> Result<()> {
+    let client = GpgWalletClient::connect(network);
+    let key_id = get_key_id(sub_m).await?;
+    let to_address = sub_m
+        .get_one::<String>("destination")
+        .context("Destination address not provided")?;
+    let to_address = filter_hex_string(to_address.strip_prefix("0x").unwrap_or(to_address));
+    client.verify_chain_id().await?;
+    let paymaster_fee = get_paymaster_fee(sub_m, &client, SWEEP_GAS_ESTIMATE).await?;
+    let valid_for = sub_m
+        .get_one::<u64>("valid_for")
+        .copied()
+        .unwrap_or(PREPARED_REQUEST_TTL_SECS);
+    let signing_data = client
+        .get_signable_hash_with_deadline(
+            &key_id,
+            &to_address,
+            paymaster_fee,
+            U256::from(unix_now() + valid_for),
+        )
+        .await
+        .context("Failed to get signable hash")?;
+    let request = client
+        .prepare_signing_request(&key_id, &signing_data)
+        .await?;
+    request.save(path)?;
+    output::emit(request_record(&request, path));
+    describe_request(&request, Some(network));
+    say!(
+        "{} {}",
+        "Signing request written to".blue().bold(),
+        path.display().to_string().green()
+    );
+    say!(
+        "\tSign it offline with `sign-request`, then broadcast it with `submit` within {valid_for}s"
+    );
+    Ok(())
+}
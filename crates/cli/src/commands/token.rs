@@ -0,0 +1,145 @@
+use crate::commands::{
+    connect_for_signing, get_key_id, get_paymaster_fee, relay, sign_with_key, signed_record,
+    with_tx,
+};
+use crate::output::{self, say};
+use crate::utils::{filter_hex_string, hex_color};
+use anyhow::{Context, Result};
+use clap::ArgMatches;
+use colored::Colorize;
+use libtea_gpg_wallet::{
+    client::{EXECUTE_GAS_ESTIMATE, GpgWalletClient},
+    network::Network,
+    utils::{decimal_to_units, units_to_decimal},
+};
+use serde_json::json;
+
+pub(crate) async fn handle_token_balance(sub_m: &ArgMatches, network: &Network) -> Result<()> {
+    let client = GpgWalletClient::connect(network);
+    let key_id = get_key_id(sub_m).await?;
+    let token = sub_m
+        .get_one::<String>("token")
+        .context("Token address not provided")?;
+    let token = client.token_info(token).await?;
+    let wallet = client.deployed_address(&key_id).await?;
+    let balance = client.token(token.address).balanceOf(wallet).call().await?;
+    output::emit(json!({
+        "keyId": key_id,
+        "address": wallet,
+        "token": token.address,
+        "symbol": token.symbol,
+        "decimals": token.decimals,
+        "balanceRaw": balance.to_string(),
+        "balance": units_to_decimal(balance, token.decimals as usize),
+    }));
+    say!(
+        "{} {} {}: {}",
+        token.symbol.blue().bold(),
+        "balance for key ID".blue().bold(),
+        hex_color(&key_id, 4)?,
+        token.format(balance).green()
+    );
+    Ok(())
+}
+
+// token-withdraw and token-sweep differ only in where the amount comes from
+pub(crate) async fn handle_token_withdraw(
+    sub_m: &ArgMatches,
+    network: &Network,
+    sweep: bool,
+) -> Result<()> {
+    let (client, relayer) = connect_for_signing(sub_m, network)?;
+    let key_id = get_key_id(sub_m).await?;
+    let token = sub_m
+        .get_one::<String>("token")
+        .context("Token address not provided")?;
+    let to_address = sub_m
+        .get_one::<String>("destination")
+        .context("Destination address not provided")?;
+    let to_address = filter_hex_string(to_address.strip_prefix("0x").unwrap_or(to_address));
+    client.verify_chain_id().await?;
+    let token = client.token_info(token).await?;
+    let balance = client.get_key_id_token_balance(&key_id, &token).await?;
+    say!(
+        "{} {} {}: {}",
+        token.symbol.blue().bold(),
+        "balance for key ID".blue().bold(),
+        hex_color(&key_id, 4)?,
+        token.format(balance).green()
+    );
+    let amount = if sweep {
+        balance
+    } else {
+        let amount_str = sub_m
+            .get_one::<String>("amount")
+            .context("Amount not provided")?;
+        decimal_to_units(amount_str, token.decimals as usize)?
+    };
+    if amount.is_zero() {
+        eprintln!("No {} to withdraw for key ID {key_id}", token.symbol);
+        return Ok(());
+    }
+    let paymaster_fee = get_paymaster_fee(sub_m, &client, EXECUTE_GAS_ESTIMATE).await?;
+    let pb = output::spinner();
+    pb.set_message(format!(
+        "Withdrawing {} from key ID {} to 0x{}",
+        token.format(amount).green(),
+        hex_color(&key_id, 4)?,
+        to_address.green()
+    ));
+    let signing_data = client
+        .get_token_withdraw_signable_hash(&key_id, &token, &to_address, amount, paymaster_fee)
+        .await
+        .context("Failed to get signable hash")?;
+    let signature = sign_with_key(sub_m, &signing_data).await?;
+    let tx = match &relayer {
+        Some(relayer) => relay(&client, relayer, &key_id, &signing_data, &signature, &pb).await?,
+        None => {
+            client
+                .token_withdraw(
+                    &key_id,
+                    &signing_data,
+                    &signature.public_key,
+                    &signature.signature,
+                )
+                .await
+                .context("Failed to withdraw tokens from GPG wallet")?
+                .tx_hash
+        }
+    };
+    pb.finish_with_message("Token withdrawal completed".green().to_string());
+    let new_balance = client
+        .token(token.address)
+        .balanceOf(signing_data.wallet)
+        .call()
+        .await?;
+    let mut record = signed_record(&key_id, &signing_data, network, relayer.is_some());
+    record["token"] = json!(token.address);
+    record["tokenSymbol"] = json!(token.symbol);
+    record["decimals"] = json!(token.decimals);
+    record["recipient"] = json!(format!("0x{to_address}"));
+    record["amountRaw"] = json!(amount.to_string());
+    record["amount"] = json!(units_to_decimal(amount, token.decimals as usize));
+    let mut record = with_tx(record, &client, tx).await?;
+    record["balanceRaw"] = json!(new_balance.to_string());
+    record["balance"] = json!(units_to_decimal(new_balance, token.decimals as usize));
+    output::emit(record);
+    say!(
+        "{} {}: {}",
+        "Token withdrawal transaction hash for key ID".blue().bold(),
+        hex_color(&key_id, 4)?,
+        hex_color(tx.to_string(), 4)?
+    );
+    if let Some(url) = network.tx_url(tx) {
+        say!("\t{}", url.to_string().dimmed());
+    }
+    say!(
+        "{} {} {} {}: {}",
+        "New".blue().bold(),
+        token.symbol.blue().bold(),
+        "balance for key ID".blue().bold(),
+        hex_color(&key_id, 4)?,
+        token.format(new_balance).green()
+    );
+    Ok(())
+}
@@ -0,0 +1,254 @@
+use crate::commands::history::{describe_history_entry, history_entry_json};
+use crate::commands::{get_key_ids, tx_link};
+use crate::output::{self, OutputFormat, say};
+use crate::utils::{format_native, hex_color, wei_to_eth_auto};
+use alloy::providers::{DynProvider, Provider};
+use anyhow::Result;
+use clap::ArgMatches;
+use colored::Colorize;
+use libtea_gpg_wallet::{
+    Error,
+    client::GpgWalletClient,
+    network::Network,
+    watch::{DEFAULT_CONFIRMATIONS, WatchChange, WatchEvent, Watcher},
+};
+use serde_json::json;
+use std::time::Duration;
+
+// Backoff between reconnects after an RPC failure while watching
+const WATCH_RETRY_MIN: Duration = Duration::from_secs(2);
+const WATCH_RETRY_MAX: Duration = Duration::from_secs(60);
+// Every field any watch event has, since a CSV stream can't change columns
+const WATCH_CSV_COLUMNS: &[&str] = &[
+    "event",
+    "block",
+    "time",
+    "keyId",
+    "wallet",
+    "txHash",
+    "kind",
+    "from",
+    "to",
+    "amountWei",
+    "amount",
+    "paymasterFeeWei",
+    "paymasterFee",
+    "nonce",
+    "data",
+    "success",
+    "previousWei",
+    "previous",
+    "balanceWei",
+    "balance",
+    "symbol",
+    "deployed",
+    "nextNonce",
+];
+
+pub(crate) async fn handle_watch(sub_m: &ArgMatches, network: &Network) -> Result<()> {
+    let key_ids = get_key_ids(sub_m).await?;
+    let confirmations = sub_m
+        .get_one::<u64>("confirmations")
+        .copied()
+        .unwrap_or(DEFAULT_CONFIRMATIONS);
+    let poll_interval =
+        Duration::from_secs(sub_m.get_one::<u64>("poll_interval").copied().unwrap_or(5));
+    let ws_url = sub_m
+        .get_one::<String>("ws_url")
+        .cloned()
+        .or_else(|| network.ws_url.as_ref().map(|url| url.to_string()));
+
+    let client = GpgWalletClient::connect(network);
+    client.verify_chain_id().await?;
+    let wallets = if key_ids.len() > 1 {
+        client
+            .lookup_key_ids(&key_ids)
+            .await?
+            .into_iter()
+            .map(|wallet| (wallet.key_id, wallet.address))
+            .collect()
+    } else {
+        let address = client.predict_address(&key_ids[0]).await?.walletAddress;
+        vec![(key_ids[0].clone(), address)]
+    };
+    let mut watcher = Watcher::new(wallets, confirmations);
+    if output::format() == OutputFormat::Csv {
+        println!("{}", WATCH_CSV_COLUMNS.join(","));
+    }
+
+    // Both only return on failure; the watcher picks up where it stopped
+    let mut retry = WATCH_RETRY_MIN;
+    loop {
+        let last_block = watcher.last_block();
+        let result = match &ws_url {
+            Some(url) => watch_ws(&mut watcher, network, url).await,
+            None => watch_http(&mut watcher, network, poll_interval).await,
+        };
+        if let Err(e) = result {
+            if watcher.last_block() != last_block {
+                retry = WATCH_RETRY_MIN;
+            }
+            eprintln!(
+                "{}: {e}; reconnecting in {}s",
+                "RPC failure".yellow().bold(),
+                retry.as_secs()
+            );
+            tokio::time::sleep(retry).await;
+            retry = (retry * 2).min(WATCH_RETRY_MAX);
+        }
+    }
+}
+
+async fn watch_http(
+    watcher: &mut Watcher,
+    network: &Network,
+    poll_interval: Duration,
+) -> Result<()> {
+    let client = GpgWalletClient::connect(network);
+    client.verify_chain_id().await?;
+    loop {
+        let head = client.provider().get_block_number().await?;
+        catch_up(watcher, &client, head).await?;
+        tokio::time::sleep(poll_interval).await;
+    }
+}
+
+async fn watch_ws(watcher: &mut Watcher, network: &Network, url: &str) -> Result<()> {
+    let client = GpgWalletClient::connect_ws(network, url).await?;
+    client.verify_chain_id().await?;
+    let mut heads = client.provider().subscribe_blocks().await?;
+    let head = client.provider().get_block_number().await?;
+    catch_up(watcher, &client, head).await?;
+    loop {
+        let header = heads.recv().await?;
+        catch_up(watcher, &client, header.number).await?;
+    }
+}
+
+// Reports every block confirmed as of `head`
+async fn catch_up(
+    watcher: &mut Watcher,
+    client: &GpgWalletClient<DynProvider>,
+    head: u64,
+) -> Result<()> {
+    loop {
+        let starting = watcher.last_block().is_none();
+        match watcher.advance(client, head).await {
+            Ok(Some(events)) => {
+                if starting {
+                    print_watching(watcher, client.network())?;
+                }
+                for event in &events {
+                    print_watch_event(event, client.network())?;
+                }
+            }
+            Ok(None) => return Ok(()),
+            Err(e @ Error::Reorg(_)) => eprintln!("{}: {e}", "Reorg".yellow().bold()),
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+fn print_watching(watcher: &Watcher, network: &Network) -> Result<()> {
+    let block = watcher.last_block().unwrap_or_default();
+    for wallet in watcher.wallets() {
+        emit_watch_record(json!({
+            "event": "watching",
+            "block": block,
+            "keyId": wallet.key_id,
+            "wallet": wallet.address,
+            "balanceWei": wallet.state.balance.to_string(),
+            "balance": wei_to_eth_auto(wallet.state.balance),
+            "symbol": network.native_symbol,
+            "deployed": wallet.state.is_deployed,
+            "nextNonce": wallet.state.next_nonce.to_string(),
+        }));
+        let deployed = if wallet.state.is_deployed {
+            format!("deployed, next nonce {}", wallet.state.next_nonce).green()
+        } else {
+            "not deployed".red()
+        };
+        say!(
+            "{} {} ({}) {} {block}: {} ({deployed})",
+            "Watching".blue().bold(),
+            hex_color(&wallet.key_id, 4)?,
+            hex_color(wallet.address.to_string(), 4)?,
+            "from block".blue().bold(),
+            format_native(wallet.state.balance, network).green(),
+        );
+    }
+    Ok(())
+}
+
+fn print_watch_event(event: &WatchEvent, network: &Network) -> Result<()> {
+    let (mut row, description) = match &event.change {
+        WatchChange::Deployed => (
+            json!({ "event": "deployed" }),
+            "deployed".green().bold().to_string(),
+        ),
+        WatchChange::Transaction(entry) => {
+            let mut row = history_entry_json(entry, network);
+            row["event"] = json!(entry.kind);
+            let description = format!(
+                "{} {}",
+                describe_history_entry(entry, event.wallet, network)?,
+                tx_link(entry.tx_hash, network).dimmed()
+            );
+            (row, description)
+        }
+        WatchChange::NonceChanged {
+            previous,
+            next_nonce,
+        } => (
+            json!({
+                "event": "nonceChanged",
+                "previous": previous.to_string(),
+                "nextNonce": next_nonce.to_string(),
+            }),
+            format!("next nonce {previous} -> {next_nonce}"),
+        ),
+        WatchChange::BalanceChanged { previous, balance } => (
+            json!({
+                "event": "balanceChanged",
+                "previousWei": previous.to_string(),
+                "previous": wei_to_eth_auto(*previous),
+                "balanceWei": balance.to_string(),
+                "balance": wei_to_eth_auto(*balance),
+                "symbol": network.native_symbol,
+            }),
+            format!(
+                "balance {} -> {}",
+                format_native(*previous, network),
+                format_native(*balance, network).green()
+            ),
+        ),
+    };
+    row["keyId"] = json!(event.key_id);
+    row["wallet"] = json!(event.wallet);
+    row["block"] = json!(event.block);
+    emit_watch_record(row);
+
+    let key_id = hex_color(&event.key_id, 4)?;
+    let key_id = if event.is_deposit() {
+        key_id.bold()
+    } else {
+        key_id
+    };
+    say!(
+        "{} {key_id} {description}",
+        format!("[{}]", event.block).blue(),
+    );
+    Ok(())
+}
+
+// watch streams: a JSON object or CSV row per event, as it happens
+fn emit_watch_record(row: serde_json::Value) {
+    match output::format() {
+        OutputFormat::Table => {}
+        OutputFormat::Json => println!("{row}"),
+        OutputFormat::Csv => println!(
+            "{}",
+            output::csv_line(WATCH_CSV_COLUMNS, &output::flatten(&row))
+        ),
+    }
+}
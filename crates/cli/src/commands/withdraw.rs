@@ -0,0 +1,86 @@
+use crate::commands::{
+    connect_for_signing, get_key_id, get_paymaster_fee, relay, sign_with_key, signed_record,
+    with_tx,
+};
+use crate::output::{self, say};
+use crate::utils::{filter_hex_string, format_native, hex_color, wei_to_eth_auto};
+use alloy::providers::Provider;
+use anyhow::{Context, Result};
+use clap::ArgMatches;
+use colored::Colorize;
+use libtea_gpg_wallet::{
+    client::EXECUTE_GAS_ESTIMATE, network::Network, utils::decimal_to_wei_precise,
+};
+use serde_json::json;
+
+pub(crate) async fn handle_withdraw(sub_m: &ArgMatches, network: &Network) -> Result<()> {
+    let (client, relayer) = connect_for_signing(sub_m, network)?;
+    let key_id = get_key_id(sub_m).await?;
+    let amount_str = sub_m
+        .get_one::<String>("amount")
+        .context("Amount not provided")?;
+    let amount = decimal_to_wei_precise(amount_str)?;
+    let to_address = sub_m
+        .get_one::<String>("destination")
+        .context("Destination address not provided")?;
+    let to_address = filter_hex_string(to_address.strip_prefix("0x").unwrap_or(to_address));
+    client.verify_chain_id().await?;
+    let balance = client.get_key_id_balance(&key_id).await?;
+    say!(
+        "{} {}: {}",
+        "Balance for key ID".blue().bold(),
+        hex_color(&key_id, 4)?,
+        format_native(balance, network).green()
+    );
+    let paymaster_fee = get_paymaster_fee(sub_m, &client, EXECUTE_GAS_ESTIMATE).await?;
+    let pb = output::spinner();
+    pb.set_message(format!(
+        "Withdrawing {} from key ID {} to 0x{}",
+        format_native(amount, network).green(),
+        hex_color(&key_id, 4)?,
+        to_address.green()
+    ));
+    let signing_data = client
+        .get_withdraw_signable_hash(&key_id, &to_address, amount, paymaster_fee)
+        .await
+        .context("Failed to get signable hash")?;
+    let signature = sign_with_key(sub_m, &signing_data).await?;
+    let tx = match &relayer {
+        Some(relayer) => relay(&client, relayer, &key_id, &signing_data, &signature, &pb).await?,
+        None => {
+            client
+                .execute_with_sig(
+                    &key_id,
+                    &signing_data,
+                    &signature.public_key,
+                    &signature.signature,
+                )
+                .await
+                .context("Failed to withdraw from GPG wallet")?
+                .tx_hash
+        }
+    };
+    pb.finish_with_message("Withdrawal completed".green().to_string());
+    let new_balance = client.provider().get_balance(signing_data.wallet).await?;
+    let record = signed_record(&key_id, &signing_data, network, relayer.is_some());
+    let mut record = with_tx(record, &client, tx).await?;
+    record["balanceWei"] = json!(new_balance.to_string());
+    record["balance"] = json!(wei_to_eth_auto(new_balance));
+    output::emit(record);
+    say!(
+        "{} {}: {}",
+        "Withdrawal transaction hash for key ID".blue().bold(),
+        hex_color(&key_id, 4)?,
+        hex_color(tx.to_string(), 4)?
+    );
+    if let Some(url) = network.tx_url(tx) {
+        say!("\t{}", url.to_string().dimmed());
+    }
+    say!(
+        "{} {}: {}",
+        "New balance for key ID".blue().bold(),
+        hex_color(&key_id, 4)?,
+        format_native(new_balance, network).green()
+    );
+    Ok(())
+}
@@ -53,6 +53,68 @@ pub async fn get_key_id(email: &str) -> Result<String> {
     Ok(key_id)
 }
 
+/// A primary key from the local keyring
+#[derive(Debug, PartialEq, Eq)]
+pub struct GpgKey {
+    pub key_id: String,
+    /// The first user ID, if the key has one
+    pub uid: Option<String>,
+    /// Whether the keyring also holds the secret key
+    pub secret: bool,
+}
+
+// Lists every primary public key in the keyring, marking those with a secret key
+pub async fn list_keys() -> Result<Vec<GpgKey>> {
+    Ok(merge_listings(
+        &run_listing("--list-keys")?,
+        &run_listing("--list-secret-keys")?,
+    ))
+}
+
+fn merge_listings(public: &str, secret: &str) -> Vec<GpgKey> {
+    let mut keys = parse_key_listing(public, "pub");
+    let secret = parse_key_listing(secret, "sec");
+    for key in &mut keys {
+        key.secret = secret.iter().any(|s| s.key_id == key.key_id);
+    }
+    keys
+}
+
+fn run_listing(command: &str) -> Result<String> {
+    let output = Command::new("gpg")
+        .arg(command)
+        .arg("--with-colons")
+        .output()
+        .context("Failed to list GPG keys")?;
+    if !output.status.success() {
+        return Err(anyhow!("gpg command failed with status: {}", output.status));
+    }
+    String::from_utf8(output.stdout).context("Failed to convert GPG key listing to string")
+}
+
+// `--with-colons` output: a `pub`/`sec` record per primary key (key ID in
+// field 5), followed by its `uid` records (user ID in field 10)
+fn parse_key_listing(listing: &str, record: &str) -> Vec<GpgKey> {
+    let mut keys: Vec<GpgKey> = Vec::new();
+    for line in listing.lines() {
+        let fields: Vec<&str> = line.split(':').collect();
+        match fields.as_slice() {
+            [kind, _, _, _, key_id, ..] if *kind == record => keys.push(GpgKey {
+                key_id: filter_hex_string(key_id),
+                uid: None,
+                secret: record == "sec",
+            }),
+            ["uid", _, _, _, _, _, _, _, _, uid, ..] => {
+                if let Some(key) = keys.last_mut().filter(|key| key.uid.is_none()) {
+                    key.uid = Some(uid.replace("\\x3a", ":"));
+                }
+            }
+            _ => {}
+        }
+    }
+    keys
+}
+
 pub async fn sign_blob(signing_data: &SigningData, key_id: &str) -> Result<SigningResult> {
     // echo "0x8941bd5962cdb275a3f5f1ffa623aa3be1fc40f55b0b308ab906cf9f7ef39cac" | xxd -r -p | gpg -u 95469C7E3DFC90B1 --pinentry-mode loopback --detach-sign | xxd -p | tr -d '\n'
     let mut proc = Command::new("gpg")
@@ -100,3 +162,65 @@ pub async fn sign_blob(signing_data: &SigningData, key_id: &str) -> Result<Signi
         public_key,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUBLIC_LISTING: &str = include_str!("../tests/fixtures/gpg-list-keys.txt");
+    const SECRET_LISTING: &str = include_str!("../tests/fixtures/gpg-list-secret-keys.txt");
+
+    fn key(key_id: &str, uid: Option<&str>, secret: bool) -> GpgKey {
+        GpgKey {
+            key_id: key_id.to_string(),
+            uid: uid.map(str::to_string),
+            secret,
+        }
+    }
+
+    #[test]
+    fn test_parse_key_listing() {
+        // Subkeys are skipped, and only each key's first user ID is kept
+        assert_eq!(
+            parse_key_listing(PUBLIC_LISTING, "pub"),
+            [
+                key(
+                    "95469C7E3DFC90B1",
+                    Some("Alice Example <alice@example.com>"),
+                    false
+                ),
+                key(
+                    "1234ABCD5678EF90",
+                    Some("Bob: Builder <bob@example.com>"),
+                    false
+                ),
+                key("CAFEF00DDEADBEEF", None, false),
+            ]
+        );
+        assert_eq!(
+            parse_key_listing(SECRET_LISTING, "sec"),
+            [key(
+                "95469C7E3DFC90B1",
+                Some("Alice Example <alice@example.com>"),
+                true
+            )]
+        );
+        assert!(parse_key_listing(SECRET_LISTING, "pub").is_empty());
+        assert!(parse_key_listing("", "pub").is_empty());
+    }
+
+    #[test]
+    fn test_merge_listings() {
+        let keys = merge_listings(PUBLIC_LISTING, SECRET_LISTING);
+        assert_eq!(
+            keys.iter()
+                .map(|key| (key.key_id.as_str(), key.secret))
+                .collect::<Vec<_>>(),
+            [
+                ("95469C7E3DFC90B1", true),
+                ("1234ABCD5678EF90", false),
+                ("CAFEF00DDEADBEEF", false),
+            ]
+        );
+    }
+}
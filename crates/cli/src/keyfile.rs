@@ -0,0 +1,29 @@
+use std::path::Path;
+
+use alloy::hex;
+use anyhow::{Context, Result};
+use libtea_gpg_wallet::{openpgp::SecretKey, wallet::SigningData};
+
+use crate::utils::SigningResult;
+
+const PASSPHRASE_ENV: &str = "TEA_GPG_WALLET_PASSPHRASE";
+
+fn load(path: &Path) -> Result<SecretKey> {
+    let passphrase = std::env::var(PASSPHRASE_ENV).ok();
+    SecretKey::from_file(path, passphrase.as_deref())
+        .with_context(|| format!("Failed to load secret key from {}", path.display()))
+}
+
+pub async fn get_key_id(path: &Path) -> Result<String> {
+    Ok(load(path)?.key_id())
+}
+
+pub async fn sign_blob(signing_data: &SigningData, path: &Path) -> Result<SigningResult> {
+    let signature = load(path)?
+        .sign_blob(signing_data)
+        .context("Failed to sign blob with key file")?;
+    Ok(SigningResult {
+        signature: hex::encode(signature.signature),
+        public_key: hex::encode(signature.public_key),
+    })
+}
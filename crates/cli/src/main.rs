@@ -1,27 +1,31 @@
 mod bpb;
+mod commands;
 mod gpg;
+#[cfg(feature = "openpgp")]
+mod keyfile;
+mod output;
 mod utils;
 
-use crate::utils::{
-    SigningResult, filter_hex_string, hex_color, require_private_key, wei_to_eth_auto,
+use crate::commands::{
+    config, deadman, deploy, exec, find, history, inspect, list, request, send, sweep, token,
+    watch, withdraw,
 };
-use anyhow::{Context, Result, anyhow};
-use clap::{Arg, ArgAction::SetTrue, ArgGroup, ArgMatches, command};
-use colored::Colorize;
-use indicatif::{ProgressBar, ProgressStyle};
+use crate::output::OutputFormat;
+use anyhow::Result;
+use clap::{Arg, ArgAction::SetTrue, ArgGroup, command};
 use libtea_gpg_wallet::{
-    deployer::{self, get_key_id_balance, predict_address, send_to_gpg_key},
-    utils::{decimal_to_wei_precise, get_rpc_url},
-    wallet::{SigningData, get_signable_hash, sweep_gpg_key},
+    config::{Config, Overrides},
+    watch::DEFAULT_CONFIRMATIONS,
 };
-use std::time::Duration;
+use std::path::PathBuf;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // rustls + ring only (no aws-lc C build). reqwest is built with
     // rustls-no-provider; install ring process-wide before any TLS call.
     let _ = rustls::crypto::ring::default_provider().install_default();
-    let key_arguments = [
+    #[allow(unused_mut)]
+    let mut key_arguments = vec![
         Arg::new("key_id").help("The GPG key ID to map to wallet address"),
         Arg::new("bpb")
             .long("bpb")
@@ -33,18 +37,159 @@ async fn main() -> Result<()> {
             .short('g')
             .help("Use gpg to get the key ID for an email address"),
     ];
+    #[cfg(feature = "openpgp")]
+    key_arguments.push(
+        Arg::new("key_file")
+            .long("key-file")
+            .short('k')
+            .value_parser(clap::value_parser!(PathBuf))
+            .help(
+                "Sign with an OpenPGP secret key file (passphrase from TEA_GPG_WALLET_PASSPHRASE)",
+            ),
+    );
+    // Ways to name the key, and the subset of them that can also sign
+    let signers: Vec<clap::Id> = key_arguments[1..]
+        .iter()
+        .map(|arg| arg.get_id().clone())
+        .collect();
+    let key_sources: Vec<clap::Id> = key_arguments
+        .iter()
+        .map(|arg| arg.get_id().clone())
+        .collect();
+    let relay_arguments = [
+        Arg::new("relayer")
+            .long("relayer")
+            .value_name("URL")
+            .help("Submit through a tea-gpg-wallet-relayer instead of from PRIVATE_KEY"),
+        Arg::new("paymaster_fee")
+            .long("paymaster-fee")
+            .value_name("TEA|auto")
+            .help("TEA paid from the wallet to whoever submits the transaction; \"auto\" suggests one from the current gas price (default: auto with --relayer, otherwise 0)"),
+    ];
+    let tx_arguments = [
+        Arg::new("gas_limit")
+            .long("gas-limit")
+            .value_name("GAS")
+            .value_parser(clap::value_parser!(u64))
+            .help("Gas limit for each transaction (default: estimated)"),
+        Arg::new("max_fee_per_gas")
+            .long("max-fee-per-gas")
+            .value_name("GWEI")
+            .help("Most to pay per gas, base fee included; the gas price with --legacy"),
+        Arg::new("max_priority_fee")
+            .long("max-priority-fee")
+            .value_name("GWEI")
+            .conflicts_with("legacy")
+            .help("Tip per gas for the block producer"),
+        Arg::new("legacy")
+            .long("legacy")
+            .help("Send legacy transactions with a gas price, for chains without EIP-1559")
+            .action(SetTrue),
+        Arg::new("nonce")
+            .long("nonce")
+            .value_name("NONCE")
+            .value_parser(clap::value_parser!(u64))
+            .help("Nonce of the first transaction, e.g. to replace a stuck one"),
+        Arg::new("max_total_fee")
+            .long("max-total-fee")
+            .value_name("TEA")
+            .help("Abort if a transaction's gas limit times its max fee per gas exceeds this"),
+    ];
+    let dry_run = Arg::new("dry_run")
+        .long("dry-run")
+        .help("Simulate the transactions and show what they'd do and cost, without sending them")
+        .action(SetTrue);
     let m = command!()
         .subcommand_required(true)
         .arg_required_else_help(true)
-        .subcommand(command!("config").about("Prints default configuration."))
+        .arg(
+            Arg::new("config")
+                .long("config")
+                .global(true)
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("Path to a TOML config file (default: ~/.config/tea-gpg-wallet/config.toml)"),
+        )
+        .arg(
+            Arg::new("network")
+                .long("network")
+                .short('n')
+                .global(true)
+                .help("Network profile to use: tea-sepolia (default), local, or one from the config file"),
+        )
+        .arg(
+            Arg::new("rpc_url")
+                .long("rpc-url")
+                .global(true)
+                .help("RPC endpoint to use, overriding the config file and environment"),
+        )
+        .arg(
+            Arg::new("deployer")
+                .long("deployer")
+                .global(true)
+                .help("GPG wallet deployer contract address, overriding the config file and environment"),
+        )
+        .arg(
+            Arg::new("output")
+                .long("output")
+                .global(true)
+                .value_parser(OutputFormat::NAMES)
+                .default_value("table")
+                .help("Output format; json and csv are for scripts, and turn off colors and spinners"),
+        )
+        .subcommand(command!("config").about("Prints the effective configuration and where each value came from."))
         .subcommand(
             command!("find")
-                .about("Finds the GPG wallet address for a given key ID")
+                .about("Finds the GPG wallet address for one or more key IDs")
+                .arg_required_else_help(true)
+                .arg(Arg::new("offline")
+                    .long("offline")
+                    .help("Predict the address locally from a configured or cached deployer implementation, without an RPC endpoint")
+                    .action(SetTrue))
+                .arg(Arg::new("file")
+                    .long("file")
+                    .short('f')
+                    .value_name("FILE")
+                    .value_parser(clap::value_parser!(PathBuf))
+                    .help("Read key IDs from FILE, one per line ('#' starts a comment)"))
+                .args(&key_arguments)
+                .mut_arg("key_id", |arg| arg.num_args(1..).help("The GPG key ID(s) to map to wallet addresses"))
+                .group(
+                    ArgGroup::new("key_id_group")
+                        .args(&key_sources)
+                        .arg("file")
+                        .multiple(true)
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            command!("list")
+                .about("Lists the keys in the local gpg keyring, or the bpb key, with their wallets and balances")
+                .arg(Arg::new("bpb")
+                    .long("bpb")
+                    .short('b')
+                    .help("List the key bpb manages instead of the gpg keyring")
+                    .action(SetTrue))
+                .arg(Arg::new("secret")
+                    .long("secret")
+                    .short('s')
+                    .conflicts_with("bpb")
+                    .help("Only list keys whose secret key is in the keyring")
+                    .action(SetTrue))
+                .arg(Arg::new("uid")
+                    .long("uid")
+                    .short('u')
+                    .value_name("TEXT")
+                    .conflicts_with("bpb")
+                    .help("Only list keys with a user ID containing TEXT (case-insensitive)")),
+        )
+        .subcommand(
+            command!("inspect")
+                .about("Shows the on-chain state of the GPG wallet for a given key ID, flagging anything that doesn't match")
                 .arg_required_else_help(true)
                 .args(&key_arguments)
                 .group(
                     ArgGroup::new("key_id_group")
-                        .args(["key_id", "bpb", "gpg"])
+                        .args(&key_sources)
                         .required(true),
                 ),
         )
@@ -53,9 +198,11 @@ async fn main() -> Result<()> {
                 .about("Deploys the GPG wallet contract for a given key ID")
                 .arg_required_else_help(true)
                 .args(&key_arguments)
+                .args(&tx_arguments)
+                .arg(dry_run.clone())
                 .group(
                     ArgGroup::new("key_id_group")
-                        .args(["key_id", "bpb", "gpg"])
+                        .args(&key_sources)
                         .required(true),
                 ),
         )
@@ -64,218 +211,304 @@ async fn main() -> Result<()> {
                 .about("Sends TEA to the GPG wallet for a given key ID, deploying the wallet if necessary,\n  (reads the private key from the PRIVATE_KEY environment variable)")
                 .arg_required_else_help(true)
                 .arg(Arg::new("amount")
-                    .help("Amount of TEA to send")
+                    .help("Amount of TEA to send, or of the token with --token")
                     .required(true))
+                .arg(Arg::new("token")
+                    .long("token")
+                    .value_name("ADDRESS")
+                    .help("Send this ERC-20 token instead of TEA"))
                 .args(&key_arguments)
+                .args(&tx_arguments)
+                .arg(dry_run.clone())
                 .group(
                     ArgGroup::new("key_id_group")
-                        .args(["key_id", "bpb", "gpg"])
+                        .args(&key_sources)
                         .required(true),
                 )
         )
         .subcommand(
             command!("sweep")
-                .about("Sweeps the GPG wallet for a given key ID,\n  (reads the private key from the PRIVATE_KEY environment variable)")
+                .about("Sweeps the GPG wallet for a given key ID,\n  (reads the private key from the PRIVATE_KEY environment variable unless --relayer is given)")
+                .arg_required_else_help(true)
+                .arg(Arg::new("destination")
+                    .help("Address to sweep to")
+                    .required(true))
+                .args(&relay_arguments)
+                .args(&tx_arguments)
+                .arg(Arg::new("prepare")
+                    .long("prepare")
+                    .value_name("FILE")
+                    .value_parser(clap::value_parser!(PathBuf))
+                    .conflicts_with("relayer")
+                    .help("Write a signing request to FILE for sign-request on an offline machine, instead of signing here"))
+                .arg(Arg::new("valid_for")
+                    .long("valid-for")
+                    .value_name("SECONDS")
+                    .value_parser(clap::value_parser!(u64))
+                    .requires("prepare")
+                    .help("How long a prepared request stays valid (default: 3600)"))
+                .arg(Arg::new("key_id")
+                    .long("key-id")
+                    .requires("prepare")
+                    .help("The GPG key ID, when preparing a request without the key on this machine"))
+                .arg(dry_run.clone().conflicts_with("prepare"))
+                .args(&key_arguments[1..]) // Signing needs a key source that can sign, unless only preparing
+                .group(
+                    ArgGroup::new("key_id_group")
+                        .args(&key_sources)
+                        .required(true),
+                )
+        )
+        .subcommand(
+            command!("sign-request")
+                .about("Signs a request file from sweep --prepare with local gpg; needs no network access")
+                .arg_required_else_help(true)
+                .arg(Arg::new("file")
+                    .help("Signing request file")
+                    .value_parser(clap::value_parser!(PathBuf))
+                    .required(true))
+                .arg(Arg::new("out")
+                    .long("out")
+                    .short('o')
+                    .value_name("FILE")
+                    .value_parser(clap::value_parser!(PathBuf))
+                    .help("Where to write the signed request (default: overwrite the input)"))
+                .args(key_arguments.iter().filter(|arg| arg.get_id() == "key_file"))
+        )
+        .subcommand(
+            command!("submit")
+                .about("Submits a request file signed by sign-request,\n  (reads the private key from the PRIVATE_KEY environment variable unless --relayer is given)")
+                .arg_required_else_help(true)
+                .arg(Arg::new("file")
+                    .help("Signed request file")
+                    .value_parser(clap::value_parser!(PathBuf))
+                    .required(true))
+                .arg(relay_arguments[0].clone())
+                .args(&tx_arguments)
+        )
+        .subcommand(
+            command!("withdraw")
+                .about("Withdraws part of the GPG wallet balance for a given key ID,\n  (reads the private key from the PRIVATE_KEY environment variable unless --relayer is given)")
+                .arg_required_else_help(true)
+                .arg(Arg::new("amount")
+                    .help("Amount of TEA to withdraw")
+                    .required(true))
+                .arg(Arg::new("destination")
+                    .help("Address to withdraw to")
+                    .required(true))
+                .args(&relay_arguments)
+                .args(&tx_arguments)
+                .args(&key_arguments[1..]) // Signing needs a key source that can sign
+                .group(
+                    ArgGroup::new("key_id_group")
+                        .args(&signers)
+                        .required(true),
+                )
+        )
+        .subcommand(
+            command!("exec")
+                .about("Calls a contract from the GPG wallet for a given key ID,\n  (reads the private key from the PRIVATE_KEY environment variable unless --relayer is given)")
+                .arg_required_else_help(true)
+                .arg(Arg::new("target")
+                    .help("Contract address to call")
+                    .required(true))
+                .arg(Arg::new("call")
+                    .help("Function signature, e.g. 'approve(address,uint256)(bool)', or 0x-prefixed calldata")
+                    .required(true))
+                .arg(Arg::new("args")
+                    .help("Arguments for the function signature")
+                    .num_args(0..)
+                    .allow_hyphen_values(true))
+                .arg(Arg::new("value")
+                    .long("value")
+                    .help("Amount of TEA to send with the call")
+                    .default_value("0"))
+                .arg(Arg::new("returns")
+                    .long("returns")
+                    .help("Signature to decode the result with when passing raw calldata, e.g. 'f()(uint256)'"))
+                .args(&relay_arguments)
+                .args(&tx_arguments)
+                .args(&key_arguments[1..]) // Signing needs a key source that can sign
+                .group(
+                    ArgGroup::new("key_id_group")
+                        .args(&signers)
+                        .required(true),
+                )
+        )
+        .subcommand(
+            command!("token-balance")
+                .about("Shows the ERC-20 token balance of the GPG wallet for a given key ID")
+                .arg_required_else_help(true)
+                .arg(Arg::new("token")
+                    .help("ERC-20 token contract address")
+                    .required(true))
+                .args(&key_arguments)
+                .group(
+                    ArgGroup::new("key_id_group")
+                        .args(&key_sources)
+                        .required(true),
+                )
+        )
+        .subcommand(
+            command!("token-withdraw")
+                .about("Withdraws ERC-20 tokens from the GPG wallet for a given key ID,\n  (reads the private key from the PRIVATE_KEY environment variable unless --relayer is given)")
+                .arg_required_else_help(true)
+                .arg(Arg::new("token")
+                    .help("ERC-20 token contract address")
+                    .required(true))
+                .arg(Arg::new("amount")
+                    .help("Amount of tokens to withdraw, in the token's own units")
+                    .required(true))
+                .arg(Arg::new("destination")
+                    .help("Address to withdraw to")
+                    .required(true))
+                .args(&relay_arguments)
+                .args(&tx_arguments)
+                .args(&key_arguments[1..]) // Signing needs a key source that can sign
+                .group(
+                    ArgGroup::new("key_id_group")
+                        .args(&signers)
+                        .required(true),
+                )
+        )
+        .subcommand(
+            command!("token-sweep")
+                .about("Sweeps all of an ERC-20 token from the GPG wallet for a given key ID,\n  (reads the private key from the PRIVATE_KEY environment variable unless --relayer is given)")
                 .arg_required_else_help(true)
+                .arg(Arg::new("token")
+                    .help("ERC-20 token contract address")
+                    .required(true))
                 .arg(Arg::new("destination")
                     .help("Address to sweep to")
                     .required(true))
-                .args(&key_arguments[1..]) // Exclude "key_id" since we can't sweep without a private key
+                .args(&relay_arguments)
+                .args(&tx_arguments)
+                .args(&key_arguments[1..]) // Signing needs a key source that can sign
+                .group(
+                    ArgGroup::new("key_id_group")
+                        .args(&signers)
+                        .required(true),
+                )
+        )
+        .subcommand(
+            command!("history")
+                .about("Lists the deposits, withdrawals, calls and recoveries of the GPG wallet for a given key ID")
+                .after_help("Every block in the range is fetched in full and balances are read at past blocks, so this needs an archive node and a long range takes a while; use --checkpoint to resume an interrupted scan.")
+                .arg_required_else_help(true)
+                .arg(Arg::new("from_block")
+                    .long("from-block")
+                    .value_name("BLOCK")
+                    .value_parser(clap::value_parser!(u64))
+                    .help("First block to scan [default: the wallet's first deposit or deployment]"))
+                .arg(Arg::new("to_block")
+                    .long("to-block")
+                    .value_name("BLOCK")
+                    .value_parser(clap::value_parser!(u64))
+                    .help("Last block to scan [default: latest]"))
+                .arg(Arg::new("checkpoint")
+                    .long("checkpoint")
+                    .value_name("FILE")
+                    .value_parser(clap::value_parser!(PathBuf))
+                    .help("Save progress to FILE as the scan goes, and resume from it if it exists (not with --from-block)"))
+                .args(&key_arguments)
+                .group(
+                    ArgGroup::new("key_id_group")
+                        .args(&key_sources)
+                        .required(true),
+                )
+        )
+        .subcommand(
+            command!("watch")
+                .about("Follows new blocks and reports deposits, withdrawals, deployment, nonce and balance changes for one or more key IDs")
+                .arg_required_else_help(true)
+                .arg(Arg::new("file")
+                    .long("file")
+                    .short('f')
+                    .value_name("FILE")
+                    .value_parser(clap::value_parser!(PathBuf))
+                    .help("Read key IDs from FILE, one per line ('#' starts a comment)"))
+                .arg(Arg::new("confirmations")
+                    .long("confirmations")
+                    .value_name("BLOCKS")
+                    .value_parser(clap::value_parser!(u64).range(1..))
+                    .help(format!("Blocks a change waits for before it's reported, to ride out reorgs [default: {DEFAULT_CONFIRMATIONS}]")))
+                .arg(Arg::new("poll_interval")
+                    .long("poll-interval")
+                    .value_name("SECONDS")
+                    .value_parser(clap::value_parser!(u64).range(1..))
+                    .default_value("5")
+                    .help("How often to poll for new blocks over HTTP"))
+                .arg(Arg::new("ws_url")
+                    .long("ws-url")
+                    .value_name("URL")
+                    .help("Subscribe to new blocks over this WebSocket instead of polling [default: the network's ws_url, if set]"))
+                .args(&key_arguments)
+                .mut_arg("key_id", |arg| arg.num_args(1..).help("The GPG key ID(s) to watch"))
+                .group(
+                    ArgGroup::new("key_id_group")
+                        .args(&key_sources)
+                        .arg("file")
+                        .multiple(true)
+                        .required(true),
+                )
+        )
+        .subcommand(
+            command!("deadman")
+                .about("Shows the dead man switch of the GPG wallet for a given key ID; with --recover, the admin recovers its funds once it has fired,\n  (--recover reads the private key from the PRIVATE_KEY environment variable)")
+                .arg_required_else_help(true)
+                .arg(Arg::new("recover")
+                    .long("recover")
+                    .help("Run recoverPostDeadmanSwitch from the admin account")
+                    .action(SetTrue))
+                .args(&key_arguments)
+                .args(tx_arguments.iter().map(|arg| arg.clone().requires("recover")))
                 .group(
                     ArgGroup::new("key_id_group")
-                        .args(["bpb", "gpg"])
+                        .args(&key_sources)
                         .required(true),
                 )
         )
         .get_matches();
 
-    match m.subcommand() {
-        Some(("config", _)) => handle_config()?,
-        Some(("find", sub_m)) => handle_find(sub_m).await?,
-        Some(("deploy", sub_m)) => handle_deploy(sub_m).await?,
-        Some(("send", sub_m)) => handle_send(sub_m).await?,
-        Some(("sweep", sub_m)) => handle_sweep(sub_m).await?,
-        _ => unreachable!(),
-    }
-    Ok(())
-}
-
-async fn get_key_id(sub_m: &ArgMatches) -> Result<String> {
-    if sub_m.try_get_one::<String>("key_id").is_ok() && sub_m.get_one::<String>("key_id").is_some()
-    {
-        sub_m
-            .get_one::<String>("key_id")
-            .map(|s| filter_hex_string(s))
-            .context("Key ID not valid")
-    } else if sub_m.get_flag("bpb") {
-        bpb::get_key_id()
-            .await
-            .context("Failed to get key ID from bpb")
-    } else if sub_m.get_one::<String>("gpg").is_some() {
-        let email = sub_m
-            .get_one::<String>("gpg")
-            .context("Email address not provided")?;
-        gpg::get_key_id(email)
-            .await
-            .context("Failed to get key ID from gpg")
-    } else {
-        Err(anyhow!("No key ID provided"))
-    }
-}
-
-async fn sign_with_key(sub_m: &ArgMatches, blob: &SigningData) -> Result<SigningResult> {
-    if sub_m.get_flag("bpb") {
-        return bpb::sign_blob(blob)
-            .await
-            .context("Failed to sign blob with bpb");
-    } else if sub_m.get_one::<String>("gpg").is_some() {
-        let key_id = get_key_id(sub_m).await?;
-        return gpg::sign_blob(blob, &key_id)
-            .await
-            .context("Failed to sign blob with gpg");
-    }
-    Err(anyhow!("No signing method provided"))
-}
-
-fn setup_spinner() -> ProgressBar {
-    let pb = ProgressBar::new_spinner();
-    pb.enable_steady_tick(Duration::from_millis(100));
-    pb.set_style(
-        ProgressStyle::with_template("{spinner:.green} {msg}")
-            .unwrap()
-            .tick_chars("⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏"),
-    );
-    pb
-}
-
-fn handle_config() -> Result<()> {
-    println!("Default configuration:\n");
-    println!("{}", "RPC URL:".blue().bold());
-    println!("  {}", get_rpc_url()?.to_string().green());
-    println!("{}", "Deployer address:".blue().bold());
-    println!(
-        "  {}",
-        hex_color(deployer::get_contract_address()?.to_string(), 4)?
-    );
-    Ok(())
-}
-
-async fn handle_find(sub_m: &ArgMatches) -> Result<()> {
-    let key_id = get_key_id(sub_m).await?;
-    let prediction = predict_address(&key_id).await?;
-    let deployed = if prediction.isDeployed {
-        "deployed".green().bold()
-    } else {
-        "not deployed".red().bold()
-    };
-    println!(
-        "{} {}:",
-        "Predicted address for key ID".blue().bold(),
-        hex_color(&key_id, 4)?
-    );
-    println!(
-        "\t{} ({deployed})",
-        hex_color(prediction.walletAddress.to_string(), 4)?
-    );
-    Ok(())
-}
-
-async fn handle_deploy(sub_m: &ArgMatches) -> Result<()> {
-    let private_key = require_private_key()?;
-    let key_id = get_key_id(sub_m).await?;
-    let prediction = deployer::ensure_deployed(&key_id, &private_key).await?;
-    println!(
-        "{} {}:",
-        "Deployed address for key ID".blue().bold(),
-        hex_color(&key_id, 4)?
-    );
-    println!("\t{}", hex_color(prediction.walletAddress.to_string(), 4)?);
-    Ok(())
-}
-
-async fn handle_send(sub_m: &ArgMatches) -> Result<()> {
-    let private_key = require_private_key()?;
-    let key_id = get_key_id(sub_m).await?;
-    let amount_str = sub_m
-        .get_one::<String>("amount")
-        .context("Amount not provided")?;
-    let amount = decimal_to_wei_precise(amount_str)?;
-    let balance = get_key_id_balance(&key_id).await?;
-    println!(
-        "{} {}: {}",
-        "Balance for key ID".blue().bold(),
-        hex_color(&key_id, 4)?,
-        wei_to_eth_auto(balance).green()
-    );
-    let pb = setup_spinner();
-    pb.set_message(format!(
-        "Sending {} to key ID {}",
-        wei_to_eth_auto(amount).green(),
-        hex_color(&key_id, 4)?
+    output::init(OutputFormat::parse(
+        m.get_one::<String>("output")
+            .map_or("table", String::as_str),
     ));
-    let new_balance = send_to_gpg_key(&key_id, amount, &private_key).await?;
-    pb.finish_with_message("Send completed".green().to_string());
-    println!(
-        "{} {}: {}",
-        "New balance for key ID".blue().bold(),
-        hex_color(&key_id, 4)?,
-        wei_to_eth_auto(new_balance).green()
-    );
-    Ok(())
-}
 
-async fn handle_sweep(sub_m: &ArgMatches) -> Result<()> {
-    let private_key = require_private_key()?;
-    let key_id = get_key_id(sub_m).await?;
-    let to_address = sub_m
-        .get_one::<String>("destination")
-        .context("Destination address not provided")?;
-    let to_address = filter_hex_string(to_address.strip_prefix("0x").unwrap_or(to_address));
-    let balance = get_key_id_balance(&key_id).await?;
-    println!(
-        "{} {}: {}",
-        "Balance for key ID".blue().bold(),
-        hex_color(&key_id, 4)?,
-        wei_to_eth_auto(balance).green()
-    );
-    if balance.is_zero() {
-        eprintln!("No balance to sweep for key ID {key_id}");
-        return Ok(());
+    let config = Config::load(&Overrides {
+        config_path: m.get_one::<PathBuf>("config").cloned(),
+        network: m.get_one::<String>("network").cloned(),
+        rpc_url: m.get_one::<String>("rpc_url").cloned(),
+        deployer: m.get_one::<String>("deployer").cloned(),
+    })?;
+
+    match m.subcommand() {
+        Some(("config", _)) => config::handle_config(&config)?,
+        Some(("find", sub_m)) => find::handle_find(sub_m, &config.network.value).await?,
+        Some(("list", sub_m)) => list::handle_list(sub_m, &config.network.value).await?,
+        Some(("inspect", sub_m)) => inspect::handle_inspect(sub_m, &config.network.value).await?,
+        Some(("deploy", sub_m)) => deploy::handle_deploy(sub_m, &config.network.value).await?,
+        Some(("send", sub_m)) => send::handle_send(sub_m, &config.network.value).await?,
+        Some(("sweep", sub_m)) => sweep::handle_sweep(sub_m, &config.network.value).await?,
+        Some(("sign-request", sub_m)) => request::handle_sign_request(sub_m, &config).await?,
+        Some(("submit", sub_m)) => request::handle_submit(sub_m, &config.network.value).await?,
+        Some(("withdraw", sub_m)) => {
+            withdraw::handle_withdraw(sub_m, &config.network.value).await?
+        }
+        Some(("exec", sub_m)) => exec::handle_exec(sub_m, &config.network.value).await?,
+        Some(("token-balance", sub_m)) => {
+            token::handle_token_balance(sub_m, &config.network.value).await?
+        }
+        Some(("token-withdraw", sub_m)) => {
+            token::handle_token_withdraw(sub_m, &config.network.value, false).await?
+        }
+        Some(("token-sweep", sub_m)) => {
+            token::handle_token_withdraw(sub_m, &config.network.value, true).await?
+        }
+        Some(("history", sub_m)) => history::handle_history(sub_m, &config.network.value).await?,
+        Some(("watch", sub_m)) => watch::handle_watch(sub_m, &config.network.value).await?,
+        Some(("deadman", sub_m)) => deadman::handle_deadman(sub_m, &config.network.value).await?,
+        _ => unreachable!(),
     }
-    let pb = setup_spinner();
-    pb.set_message(format!(
-        "Sweeping {} from key ID {} to 0x{}",
-        wei_to_eth_auto(balance).green(),
-        hex_color(&key_id, 4)?,
-        to_address.green()
-    ));
-    let signing_data = get_signable_hash(&key_id, &to_address)
-        .await
-        .context("Failed to get signable hash")?;
-    let signature = sign_with_key(sub_m, &signing_data).await?;
-    let tx = sweep_gpg_key(
-        &key_id,
-        &to_address,
-        signing_data.deadline,
-        &signature.public_key,
-        &signature.signature,
-        &private_key,
-    )
-    .await
-    .context("Failed to sweep GPG wallet")?;
-    pb.finish_with_message("Sweep completed".green().to_string());
-    println!(
-        "{} {}: {}",
-        "Sweep transaction hash for key ID".blue().bold(),
-        hex_color(&key_id, 4)?,
-        hex_color(tx.to_string(), 4)?
-    );
-    let new_balance = get_key_id_balance(&key_id).await?;
-    println!(
-        "{} {}: {}",
-        "New balance for key ID".blue().bold(),
-        hex_color(&key_id, 4)?,
-        wei_to_eth_auto(new_balance).green()
-    );
     Ok(())
 }
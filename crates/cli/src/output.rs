@@ -0,0 +1,221 @@
+use indicatif::{ProgressBar, ProgressStyle};
+use serde_json::{Map, Value};
+use std::{io::IsTerminal, sync::OnceLock, time::Duration};
+
+/// What `--output` asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Colorized prose for people
+    Table,
+    /// One JSON document per command; `watch` prints one object per line
+    Json,
+    /// A header row, then one row per record, nested fields flattened with dots
+    Csv,
+}
+
+impl OutputFormat {
+    pub const NAMES: [&str; 3] = ["table", "json", "csv"];
+
+    pub fn parse(name: &str) -> OutputFormat {
+        match name {
+            "json" => OutputFormat::Json,
+            "csv" => OutputFormat::Csv,
+            _ => OutputFormat::Table,
+        }
+    }
+}
+
+static FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+// Colors are dropped when they'd end up in a pipe or file
+pub fn init(format: OutputFormat) {
+    let _ = FORMAT.set(format);
+    if format != OutputFormat::Table || !std::io::stdout().is_terminal() {
+        colored::control::set_override(false);
+    }
+}
+
+pub fn format() -> OutputFormat {
+    FORMAT.get().copied().unwrap_or(OutputFormat::Table)
+}
+
+pub fn is_table() -> bool {
+    format() == OutputFormat::Table
+}
+
+// println!, but only for --output table
+macro_rules! say {
+    ($($arg:tt)*) => {
+        if $crate::output::is_table() {
+            println!($($arg)*);
+        }
+    };
+}
+pub(crate) use say;
+
+// A progress spinner, hidden unless a person is watching a table
+pub fn spinner() -> ProgressBar {
+    if !is_table() || !std::io::stdout().is_terminal() {
+        return ProgressBar::hidden();
+    }
+    let pb = ProgressBar::new_spinner();
+    pb.enable_steady_tick(Duration::from_millis(100));
+    pb.set_style(
+        ProgressStyle::with_template("{spinner:.green} {msg}")
+            .unwrap()
+            .tick_chars("⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏"),
+    );
+    pb
+}
+
+// Prints a command's one result as JSON or a one-row CSV; nothing for tables
+pub fn emit(record: Value) {
+    match format() {
+        OutputFormat::Table => {}
+        OutputFormat::Json => println!("{record:#}"),
+        OutputFormat::Csv => emit_csv(std::slice::from_ref(&record)),
+    }
+}
+
+// Prints a command's results as a JSON array or CSV rows; nothing for tables
+pub fn emit_rows(rows: &[Value]) {
+    match format() {
+        OutputFormat::Table => {}
+        OutputFormat::Json => println!("{:#}", Value::from(rows.to_vec())),
+        OutputFormat::Csv => emit_csv(rows),
+    }
+}
+
+// Columns come from the first row, so every row of a command shares them
+fn emit_csv(rows: &[Value]) {
+    let rows = rows.iter().map(flatten).collect::<Vec<_>>();
+    let Some(first) = rows.first() else {
+        return;
+    };
+    let columns = first.keys().map(String::as_str).collect::<Vec<_>>();
+    println!("{}", columns.join(","));
+    for row in &rows {
+        println!("{}", csv_line(&columns, row));
+    }
+}
+
+// One CSV line of `row`'s values, in `columns` order
+pub fn csv_line(columns: &[&str], row: &Map<String, Value>) -> String {
+    columns
+        .iter()
+        .map(|column| match row.get(*column) {
+            None | Some(Value::Null) => String::new(),
+            Some(Value::String(s)) => csv_escape(s),
+            Some(value) => csv_escape(&value.to_string()),
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+// Nested objects become dotted columns; arrays stay JSON in one column
+pub fn flatten(value: &Value) -> Map<String, Value> {
+    fn flatten_into(prefix: &str, value: &Value, out: &mut Map<String, Value>) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    let key = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{prefix}.{key}")
+                    };
+                    flatten_into(&key, value, out);
+                }
+            }
+            value => {
+                out.insert(prefix.to_string(), value.clone());
+            }
+        }
+    }
+    let mut out = Map::new();
+    flatten_into("", value, &mut out);
+    out
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_flatten() {
+        let row = flatten(&json!({
+            "address": "0xd7baae85d719c2e8e27a70194471ef4b6b253d33",
+            "balance": { "wei": "5", "tea": "0.000000000000000005" },
+            "tx": { "receipt": { "gasUsed": 21000 } },
+            "keys": ["95469C7E3DFC90B1", "1234ABCD5678EF90"],
+            "empty": {},
+            "deployed": true,
+            "admin": null,
+        }));
+        let expected = json!({
+            "address": "0xd7baae85d719c2e8e27a70194471ef4b6b253d33",
+            "balance.wei": "5",
+            "balance.tea": "0.000000000000000005",
+            "tx.receipt.gasUsed": 21000,
+            "keys": ["95469C7E3DFC90B1", "1234ABCD5678EF90"],
+            "deployed": true,
+            "admin": null,
+        });
+        // Columns keep the record's field order; an empty object has none
+        assert_eq!(
+            row.keys().map(String::as_str).collect::<Vec<_>>(),
+            [
+                "address",
+                "balance.wei",
+                "balance.tea",
+                "tx.receipt.gasUsed",
+                "keys",
+                "deployed",
+                "admin"
+            ]
+        );
+        assert_eq!(Value::Object(row), expected);
+        assert_eq!(
+            flatten(&json!(42)),
+            Map::from_iter([(String::new(), json!(42))])
+        );
+    }
+
+    #[test]
+    fn test_csv_line() {
+        let row = flatten(&json!({
+            "name": "Alice, \"Al\" Example",
+            "note": "line one\nline two",
+            "plain": "0xabc",
+            "count": 3,
+            "missing": null,
+            "keys": ["a", "b"],
+        }));
+        assert_eq!(
+            csv_line(
+                &[
+                    "plain", "count", "missing", "absent", "name", "note", "keys"
+                ],
+                &row
+            ),
+            "0xabc,3,,,\"Alice, \"\"Al\"\" Example\",\"line one\nline two\",\"[\"\"a\"\",\"\"b\"\"]\""
+        );
+    }
+
+    #[test]
+    fn test_csv_escape() {
+        assert_eq!(csv_escape("plain"), "plain");
+        assert_eq!(csv_escape("a,b"), "\"a,b\"");
+        assert_eq!(csv_escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_escape("a\r\nb"), "\"a\r\nb\"");
+        assert_eq!(csv_escape(""), "");
+    }
+}
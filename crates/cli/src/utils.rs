@@ -1,6 +1,7 @@
 use alloy::primitives::U256;
 use anyhow::{Context, Result};
 use colored::{ColoredString, Colorize};
+use libtea_gpg_wallet::{network::Network, utils::units_to_decimal};
 
 pub const ETH_DECIMALS: usize = 18;
 
@@ -56,35 +57,43 @@ pub fn require_private_key() -> Result<String> {
 /// Convert wei to ETH with automatic decimal formatting
 /// Removes trailing zeros and unnecessary decimal places
 pub fn wei_to_eth_auto(wei: U256) -> String {
-    let wei_str = wei.to_string();
-
-    if wei_str.len() <= ETH_DECIMALS {
-        // Pad with leading zeros if needed
-        let mut padded = wei_str.clone();
-        while padded.len() < ETH_DECIMALS {
-            padded.insert(0, '0');
-        }
+    units_to_decimal(wei, ETH_DECIMALS)
+}
 
-        // Insert decimal point
-        if padded.len() == ETH_DECIMALS {
-            padded.insert(0, '0');
-        }
-        padded.insert(padded.len() - ETH_DECIMALS, '.');
+// Formats a wei amount in the network's native token, e.g. "1.5 TEA"
+pub fn format_native(wei: U256, network: &Network) -> String {
+    format!("{} {}", wei_to_eth_auto(wei), network.native_symbol)
+}
 
-        // Remove trailing zeros
-        padded
-            .trim_end_matches('0')
-            .trim_end_matches('.')
-            .to_string()
-    } else {
-        // Large number, split at the right position
-        let mut result = wei_str.clone();
-        result.insert(wei_str.len() - ETH_DECIMALS, '.');
+// Formats a Unix timestamp as a UTC date and time, e.g. "2025-01-31 12:00:00 UTC"
+pub fn format_unix_time(secs: u64) -> String {
+    // Civil-from-days, after Howard Hinnant's date algorithms
+    let days = (secs / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    let time = secs % 86_400;
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC",
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60
+    )
+}
 
-        // Remove trailing zeros
-        result
-            .trim_end_matches('0')
-            .trim_end_matches('.')
-            .to_string()
+// Formats a span of seconds coarsely, e.g. "3d 4h 5m"
+pub fn format_duration(secs: u64) -> String {
+    let (days, hours, minutes) = (secs / 86_400, secs % 86_400 / 3_600, secs % 3_600 / 60);
+    match (days, hours) {
+        (0, 0) if minutes == 0 => format!("{secs}s"),
+        (0, 0) => format!("{minutes}m"),
+        (0, _) => format!("{hours}h {minutes}m"),
+        _ => format!("{days}d {hours}h {minutes}m"),
     }
 }
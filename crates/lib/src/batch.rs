@@ -0,0 +1,196 @@
+use alloy::{
+    primitives::{Address, U256, address},
+    providers::Provider,
+    sol,
+    sol_types::SolCall,
+};
+
+use crate::{
+    Error, Result, client::GpgWalletClient, deployer::GpgRewardDeployer, utils::key_id_to_bytes,
+};
+
+sol!(
+    #[sol(rpc)]
+    interface IMulticall3 {
+        struct Call3 {
+            address target;
+            bool allowFailure;
+            bytes callData;
+        }
+
+        struct Result {
+            bool success;
+            bytes returnData;
+        }
+
+        function aggregate3(Call3[] calldata calls) external payable returns (Result[] memory returnData);
+        function getEthBalance(address addr) external view returns (uint256 balance);
+    }
+);
+
+/// Where Multicall3 lives on nearly every chain
+pub const MULTICALL3_ADDRESS: Address = address!("0xcA11bde05977b3631167028862bE2a173976CA11");
+
+/// Calls per `aggregate3`, to stay well under RPC gas and response size limits
+pub const MULTICALL_CHUNK_SIZE: usize = 250;
+
+/// A key ID's wallet, as resolved by a batch lookup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyWallet {
+    pub key_id: String,
+    pub address: Address,
+    pub is_deployed: bool,
+    /// The balance, deployed or not; a predicted address can be funded early
+    pub balance: U256,
+}
+
+impl<P: Provider> GpgWalletClient<P> {
+    pub fn multicall(&self) -> IMulticall3::IMulticall3Instance<&P> {
+        IMulticall3::new(self.network().multicall3, self.provider())
+    }
+
+    /// Resolves many key IDs in a few `aggregate3` calls: one pass of
+    /// `predictAddress`, then one of `getEthBalance`
+    pub async fn lookup_key_ids(&self, key_ids: &[String]) -> Result<Vec<KeyWallet>> {
+        self.lookup_key_ids_in_chunks(key_ids, MULTICALL_CHUNK_SIZE)
+            .await
+    }
+
+    async fn lookup_key_ids_in_chunks(
+        &self,
+        key_ids: &[String],
+        chunk_size: usize,
+    ) -> Result<Vec<KeyWallet>> {
+        let predictions = key_ids
+            .iter()
+            .map(|key_id| {
+                Ok(GpgRewardDeployer::predictAddressCall::new((
+                    key_id_to_bytes(key_id)?,
+                )))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let predictions = self
+            .aggregate(self.network().deployer, &predictions, chunk_size)
+            .await?;
+        let balances = predictions
+            .iter()
+            .map(|prediction| IMulticall3::getEthBalanceCall {
+                addr: prediction.walletAddress,
+            })
+            .collect::<Vec<_>>();
+        let balances = self
+            .aggregate(self.network().multicall3, &balances, chunk_size)
+            .await?;
+        Ok(key_ids
+            .iter()
+            .zip(predictions)
+            .zip(balances)
+            .map(|((key_id, prediction), balance)| KeyWallet {
+                key_id: key_id.clone(),
+                address: prediction.walletAddress,
+                is_deployed: prediction.isDeployed,
+                balance,
+            })
+            .collect())
+    }
+
+    /// Makes `calls` to `target` through `aggregate3`, `chunk_size` at a time
+    async fn aggregate<C: SolCall>(
+        &self,
+        target: Address,
+        calls: &[C],
+        chunk_size: usize,
+    ) -> Result<Vec<C::Return>> {
+        let mut results = Vec::with_capacity(calls.len());
+        for chunk in calls.chunks(chunk_size) {
+            let chunk = chunk
+                .iter()
+                .map(|call| IMulticall3::Call3 {
+                    target,
+                    allowFailure: false,
+                    callData: call.abi_encode().into(),
+                })
+                .collect();
+            for result in self.multicall().aggregate3(chunk).call().await? {
+                results.push(C::abi_decode_returns(&result.returnData).map_err(|e| {
+                    Error::InvalidInput {
+                        what: "multicall return data",
+                        reason: e.to_string(),
+                    }
+                })?);
+            }
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::Network;
+    use alloy::{
+        primitives::Bytes,
+        providers::{ProviderBuilder, mock::Asserter},
+        sol_types::SolValue,
+    };
+
+    fn push_results(asserter: &Asserter, returns: Vec<Vec<u8>>) {
+        let results = returns
+            .into_iter()
+            .map(|data| IMulticall3::Result {
+                success: true,
+                returnData: data.into(),
+            })
+            .collect::<Vec<_>>();
+        asserter.push_success(&Bytes::from(results.abi_encode()));
+    }
+
+    #[tokio::test]
+    async fn test_lookup_key_ids() -> Result<()> {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        let client = GpgWalletClient::new(provider, Network::tea_sepolia());
+        let key_ids = ["95469C7E3DFC90B1", "0000000000000001", "0000000000000002"]
+            .map(String::from)
+            .to_vec();
+        let wallets = [1, 2, 3].map(Address::repeat_byte);
+
+        // Chunks of two: predictions take two calls, then balances two more
+        push_results(
+            &asserter,
+            vec![
+                (wallets[0], true).abi_encode_params(),
+                (wallets[1], false).abi_encode_params(),
+            ],
+        );
+        push_results(&asserter, vec![(wallets[2], true).abi_encode_params()]);
+        push_results(
+            &asserter,
+            vec![U256::from(10).abi_encode(), U256::ZERO.abi_encode()],
+        );
+        push_results(&asserter, vec![U256::from(30).abi_encode()]);
+
+        let resolved = client.lookup_key_ids_in_chunks(&key_ids, 2).await?;
+        assert_eq!(resolved.len(), 3);
+        assert_eq!(
+            resolved[0],
+            KeyWallet {
+                key_id: key_ids[0].clone(),
+                address: wallets[0],
+                is_deployed: true,
+                balance: U256::from(10),
+            }
+        );
+        assert!(!resolved[1].is_deployed);
+        assert_eq!(resolved[2].address, wallets[2]);
+        assert_eq!(resolved[2].balance, U256::from(30));
+
+        assert!(
+            client
+                .lookup_key_ids(&["not a key".to_string()])
+                .await
+                .is_err()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,714 @@
+use crate::{
+    Error, Result,
+    deployer::GpgRewardDeployer::{self, GpgRewardDeployerInstance},
+    eip712::{check_struct_hash, wallet_domain},
+    network::Network,
+    tx::TxOptions,
+    utils::{key_id_to_bytes, parse_address, parse_bytes, unix_now},
+    wallet::{Execution, GpgRewardWallet::GpgRewardWalletInstance, Operation, SigningData},
+};
+use alloy::{
+    primitives::{Address, Bytes, FixedBytes, TxHash, U256},
+    providers::{DynProvider, Provider, ProviderBuilder, WsConnect},
+    rpc::types::TransactionRequest,
+    signers::local::PrivateKeySigner,
+};
+use std::sync::{Arc, atomic::AtomicU64};
+
+/// How long a signed withdrawal stays valid
+pub const SIGNATURE_TTL_SECS: u64 = 120;
+
+/// Gas a `withdrawAll` typically uses, GPG signature verification included
+pub const SWEEP_GAS_ESTIMATE: u64 = 150_000;
+
+/// Gas an `executeWithSig` of a transfer typically uses; heavier calls need more
+pub const EXECUTE_GAS_ESTIMATE: u64 = 200_000;
+
+/// Headroom added to suggested paymaster fees, for gas price movement
+pub const PAYMASTER_FEE_MARGIN_PERCENT: u64 = 20;
+
+/// A mined transfer into a GPG wallet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Transfer {
+    pub tx_hash: TxHash,
+    /// The wallet the transfer went to
+    pub wallet: Address,
+    /// The wallet's balance afterwards, in the transferred asset's smallest unit
+    pub new_balance: U256,
+}
+
+/// A connection to one network's GPG wallet deployer, shared by every operation
+///
+/// Read-only operations work with any provider; operations that send
+/// transactions need a provider with a wallet filler, such as the one built
+/// by [`GpgWalletClient::connect_with_signer`].
+#[derive(Debug, Clone)]
+pub struct GpgWalletClient<P> {
+    provider: P,
+    network: Network,
+    tx_options: TxOptions,
+    /// The address transactions are sent from, when known
+    sender: Option<Address>,
+    /// Transactions sent so far with `tx_options.nonce`
+    pub(crate) sent: Arc<AtomicU64>,
+}
+
+impl GpgWalletClient<DynProvider> {
+    /// Read-only client over HTTP to the network's primary RPC URL
+    pub fn connect(network: &Network) -> Self {
+        let provider = ProviderBuilder::new()
+            .connect_http(network.rpc_url().clone())
+            .erased();
+        Self::new(provider, network.clone())
+    }
+
+    /// Client over HTTP that signs and sends transactions with `private_key`
+    pub fn connect_with_signer(network: &Network, private_key: &str) -> Result<Self> {
+        let signer = private_key
+            .parse::<PrivateKeySigner>()
+            .map_err(Error::InvalidPrivateKey)?;
+        let sender = signer.address();
+        let provider = ProviderBuilder::new()
+            .wallet(signer)
+            .connect_http(network.rpc_url().clone())
+            .erased();
+        Ok(Self::new(provider, network.clone()).with_sender(sender))
+    }
+
+    /// Read-only client over a WebSocket, which can subscribe to new blocks
+    pub async fn connect_ws(network: &Network, ws_url: &str) -> Result<Self> {
+        let provider = ProviderBuilder::new()
+            .connect_ws(WsConnect::new(ws_url))
+            .await?
+            .erased();
+        Ok(Self::new(provider, network.clone()))
+    }
+}
+
+impl<P: Provider> GpgWalletClient<P> {
+    pub fn new(provider: P, network: Network) -> Self {
+        GpgWalletClient {
+            provider,
+            network,
+            tx_options: TxOptions::default(),
+            sender: None,
+            sent: Arc::default(),
+        }
+    }
+
+    /// Applies `options` to every transaction this client sends
+    pub fn with_tx_options(mut self, options: TxOptions) -> Self {
+        self.tx_options = options;
+        self.sent = Arc::default();
+        self
+    }
+
+    /// Sets the address transactions are estimated and simulated from
+    pub fn with_sender(mut self, sender: Address) -> Self {
+        self.sender = Some(sender);
+        self
+    }
+
+    pub fn tx_options(&self) -> &TxOptions {
+        &self.tx_options
+    }
+
+    pub fn sender(&self) -> Option<Address> {
+        self.sender
+    }
+
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    pub fn network(&self) -> &Network {
+        &self.network
+    }
+
+    pub fn deployer(&self) -> GpgRewardDeployerInstance<&P> {
+        GpgRewardDeployer::new(self.network.deployer, &self.provider)
+    }
+
+    pub fn wallet(&self, address: Address) -> GpgRewardWalletInstance<&P> {
+        GpgRewardWalletInstance::new(address, &self.provider)
+    }
+
+    /// Fails if the RPC endpoint serves a different chain than the network profile
+    pub async fn verify_chain_id(&self) -> Result<()> {
+        let chain_id = self.provider.get_chain_id().await?;
+        if chain_id != self.network.chain_id {
+            return Err(Error::ChainIdMismatch {
+                network: self.network.name.clone(),
+                expected: self.network.chain_id,
+                actual: chain_id,
+            });
+        }
+        Ok(())
+    }
+
+    /// What the deployer clones new wallets from
+    pub async fn implementation(&self) -> Result<Address> {
+        Ok(self.deployer().implementation().call().await?)
+    }
+
+    pub async fn predict_address(
+        &self,
+        key_id: &str,
+    ) -> Result<GpgRewardDeployer::predictAddressReturn> {
+        Ok(self
+            .deployer()
+            .predictAddress(key_id_to_bytes(key_id)?)
+            .call()
+            .await?)
+    }
+
+    /// Predicts the wallet address, failing if it isn't deployed yet
+    pub async fn deployed_address(&self, key_id: &str) -> Result<Address> {
+        let prediction = self.predict_address(key_id).await?;
+        if !prediction.isDeployed {
+            return Err(Error::NotDeployed(key_id.to_string()));
+        }
+        Ok(prediction.walletAddress)
+    }
+
+    pub async fn ensure_deployed(
+        &self,
+        key_id: &str,
+    ) -> Result<GpgRewardDeployer::predictAddressReturn> {
+        let prediction = self.predict_address(key_id).await?;
+        if prediction.isDeployed {
+            return Ok(prediction);
+        }
+        self.send_transaction(
+            self.deployer()
+                .deploy_call(key_id_to_bytes(key_id)?)
+                .into_transaction_request(),
+        )
+        .await?;
+        Ok(GpgRewardDeployer::predictAddressReturn {
+            walletAddress: prediction.walletAddress,
+            isDeployed: true,
+        })
+    }
+
+    pub async fn get_key_id_balance(&self, key_id: &str) -> Result<U256> {
+        let address = self.deployed_address(key_id).await?;
+        Ok(self.provider.get_balance(address).await?)
+    }
+
+    /// Sends `amount` wei to the key's wallet, deploying it first if necessary
+    pub async fn send_to_gpg_key(&self, key_id: &str, amount: U256) -> Result<Transfer> {
+        let destination = self.ensure_deployed(key_id).await?;
+
+        let send = TransactionRequest::default()
+            .to(destination.walletAddress)
+            .value(amount);
+
+        let receipt = self.send_transaction(send).await?;
+
+        Ok(Transfer {
+            tx_hash: receipt.transaction_hash,
+            wallet: destination.walletAddress,
+            new_balance: self.provider.get_balance(destination.walletAddress).await?,
+        })
+    }
+
+    /// Suggests a paymaster fee covering `gas` at the current gas price, plus a margin
+    pub async fn suggest_paymaster_fee(&self, gas: u64) -> Result<U256> {
+        let gas_price = U256::from(self.provider.get_gas_price().await?);
+        Ok(
+            gas_price * U256::from(gas) * U256::from(100 + PAYMASTER_FEE_MARGIN_PERCENT)
+                / U256::from(100),
+        )
+    }
+
+    /// Struct hash for sweeping the wallet; `paymaster_fee` wei of the balance
+    /// goes to whoever submits the transaction
+    pub async fn get_signable_hash(
+        &self,
+        key_id: &str,
+        to: &str,
+        paymaster_fee: U256,
+    ) -> Result<SigningData> {
+        let deadline = U256::from(unix_now() + SIGNATURE_TTL_SECS);
+        self.get_signable_hash_with_deadline(key_id, to, paymaster_fee, deadline)
+            .await
+    }
+
+    /// Like [`GpgWalletClient::get_signable_hash`], for signatures that need
+    /// longer than [`SIGNATURE_TTL_SECS`], e.g. from an offline machine
+    pub async fn get_signable_hash_with_deadline(
+        &self,
+        key_id: &str,
+        to: &str,
+        paymaster_fee: U256,
+        deadline: U256,
+    ) -> Result<SigningData> {
+        let address = self.deployed_address(key_id).await?;
+        self.check_balance(address, paymaster_fee).await?;
+        let wallet = self.wallet(address);
+        let nonce = wallet.nextNonce().call().await?;
+        let to = parse_address(to)?;
+        let blob = wallet
+            .getWithdrawAllStructHash(to, paymaster_fee, deadline, nonce)
+            .call()
+            .await?;
+        let signing_data = SigningData {
+            blob,
+            wallet: address,
+            deadline,
+            operation: Operation::WithdrawAll,
+            to,
+            value: U256::ZERO,
+            data: Bytes::new(),
+            paymaster_fee,
+            nonce,
+        };
+        self.check_reported_hash(&signing_data)?;
+        Ok(signing_data)
+    }
+
+    /// Struct hash for an `executeWithSig` call from the key's wallet
+    pub async fn get_execute_signable_hash(
+        &self,
+        key_id: &str,
+        to: &str,
+        value: U256,
+        data: Bytes,
+        paymaster_fee: U256,
+    ) -> Result<SigningData> {
+        let deadline = U256::from(unix_now() + SIGNATURE_TTL_SECS);
+        self.get_execute_signable_hash_with_deadline(
+            key_id,
+            to,
+            value,
+            data,
+            paymaster_fee,
+            deadline,
+        )
+        .await
+    }
+
+    /// Like [`GpgWalletClient::get_execute_signable_hash`], with a chosen deadline
+    pub async fn get_execute_signable_hash_with_deadline(
+        &self,
+        key_id: &str,
+        to: &str,
+        value: U256,
+        data: Bytes,
+        paymaster_fee: U256,
+        deadline: U256,
+    ) -> Result<SigningData> {
+        let address = self.deployed_address(key_id).await?;
+        self.check_balance(address, value + paymaster_fee).await?;
+        let wallet = self.wallet(address);
+        let nonce = wallet.nextNonce().call().await?;
+        let to = parse_address(to)?;
+        let blob = wallet
+            .getExecuteStructHash(to, value, data.clone(), paymaster_fee, deadline, nonce)
+            .call()
+            .await?;
+        let signing_data = SigningData {
+            blob,
+            wallet: address,
+            deadline,
+            operation: Operation::Execute,
+            to,
+            value,
+            data,
+            paymaster_fee,
+            nonce,
+        };
+        self.check_reported_hash(&signing_data)?;
+        Ok(signing_data)
+    }
+
+    /// Fails unless the struct hash the wallet reported is what
+    /// `signing_data`'s fields hash to locally, so a lying RPC endpoint can't
+    /// get something else signed or broadcast
+    pub(crate) fn check_reported_hash(&self, signing_data: &SigningData) -> Result<()> {
+        check_struct_hash(
+            &wallet_domain(self.network.chain_id, signing_data.wallet),
+            signing_data,
+        )
+    }
+
+    async fn check_balance(&self, wallet: Address, needed: U256) -> Result<()> {
+        if needed.is_zero() {
+            return Ok(());
+        }
+        let balance = self.provider.get_balance(wallet).await?;
+        if balance < needed {
+            return Err(Error::InsufficientFunds(format!(
+                "wallet holds {balance} wei, {needed} wei requested"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Struct hash for withdrawing `amount` wei, leaving the rest in the wallet
+    pub async fn get_withdraw_signable_hash(
+        &self,
+        key_id: &str,
+        to: &str,
+        amount: U256,
+        paymaster_fee: U256,
+    ) -> Result<SigningData> {
+        self.get_execute_signable_hash(key_id, to, amount, Bytes::new(), paymaster_fee)
+            .await
+    }
+
+    /// Submits a signed `withdrawAll`, once the struct hash and, with the
+    /// `verify` feature, the signature check out locally
+    pub async fn sweep_gpg_key(
+        &self,
+        key_id: &str,
+        to: &str,
+        paymaster_fee: U256,
+        deadline: U256,
+        public_key: &str,
+        signature: &str,
+    ) -> Result<FixedBytes<32>> {
+        let (_, tx) = self
+            .withdraw_all_request(key_id, to, paymaster_fee, deadline, public_key, signature)
+            .await?;
+        Ok(self.send_transaction(tx).await?.transaction_hash)
+    }
+
+    /// Checks a signed `withdrawAll` and builds its transaction, returning
+    /// the wallet address with it
+    pub(crate) async fn withdraw_all_request(
+        &self,
+        key_id: &str,
+        to: &str,
+        paymaster_fee: U256,
+        deadline: U256,
+        public_key: &str,
+        signature: &str,
+    ) -> Result<(Address, TransactionRequest)> {
+        if deadline < U256::from(unix_now()) {
+            return Err(Error::DeadlineExpired(format!(
+                "deadline {deadline} is in the past"
+            )));
+        }
+        let address = self.deployed_address(key_id).await?;
+        let wallet = self.wallet(address);
+        let to = parse_address(to)?;
+        let pubkey = parse_bytes("public key", public_key)?;
+        let signature = parse_bytes("signature", signature)?;
+        let nonce = wallet.nextNonce().call().await?;
+        let struct_hash = wallet
+            .getWithdrawAllStructHash(to, paymaster_fee, deadline, nonce)
+            .call()
+            .await?;
+        self.check_reported_hash(&SigningData {
+            blob: struct_hash,
+            wallet: address,
+            deadline,
+            operation: Operation::WithdrawAll,
+            to,
+            value: U256::ZERO,
+            data: Bytes::new(),
+            paymaster_fee,
+            nonce,
+        })?;
+        #[cfg(feature = "verify")]
+        self.verify_signature(address, struct_hash, &pubkey, &signature)
+            .await?;
+        Ok((
+            address,
+            wallet
+                .withdrawAll(to, paymaster_fee, deadline, pubkey, signature)
+                .into_transaction_request(),
+        ))
+    }
+
+    /// Submits a signed `executeWithSig` call
+    ///
+    /// The call is run through `eth_call` first to capture its return data,
+    /// which a mined transaction doesn't expose. The current struct hash is
+    /// recomputed locally before that, and with the `verify` feature the
+    /// signature is checked against it.
+    pub async fn execute_with_sig(
+        &self,
+        key_id: &str,
+        signing_data: &SigningData,
+        public_key: &str,
+        signature: &str,
+    ) -> Result<Execution> {
+        if signing_data.operation != Operation::Execute {
+            return Err(Error::InvalidInput {
+                what: "signing data",
+                reason: "expected an executeWithSig struct hash".to_string(),
+            });
+        }
+        if signing_data.deadline < U256::from(unix_now()) {
+            return Err(Error::DeadlineExpired(format!(
+                "deadline {} is in the past",
+                signing_data.deadline
+            )));
+        }
+        let address = self.deployed_address(key_id).await?;
+        let wallet = self.wallet(address);
+        let pubkey = parse_bytes("public key", public_key)?;
+        let signature = parse_bytes("signature", signature)?;
+        let nonce = wallet.nextNonce().call().await?;
+        let struct_hash = wallet
+            .getExecuteStructHash(
+                signing_data.to,
+                signing_data.value,
+                signing_data.data.clone(),
+                signing_data.paymaster_fee,
+                signing_data.deadline,
+                nonce,
+            )
+            .call()
+            .await?;
+        self.check_reported_hash(&SigningData {
+            blob: struct_hash,
+            wallet: address,
+            nonce,
+            ..signing_data.clone()
+        })?;
+        #[cfg(feature = "verify")]
+        self.verify_signature(address, struct_hash, &pubkey, &signature)
+            .await?;
+        let call = wallet.executeWithSig(
+            signing_data.to,
+            signing_data.value,
+            signing_data.data.clone(),
+            signing_data.paymaster_fee,
+            signing_data.deadline,
+            pubkey,
+            signature,
+        );
+        let returndata = call.call().await?;
+        let receipt = self
+            .send_transaction(call.into_transaction_request())
+            .await?;
+        Ok(Execution {
+            tx_hash: receipt.transaction_hash,
+            returndata,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eip712::struct_hash;
+    use alloy::{
+        primitives::Bytes,
+        primitives::{U64, address},
+        providers::mock::Asserter,
+        sol_types::SolValue,
+    };
+
+    const KEY_ID: &str = "95469C7E3DFC90B1";
+    const WALLET: Address = address!("0xd7baae85d719c2e8e27a70194471ef4b6b253d33");
+
+    fn mocked_client() -> (GpgWalletClient<impl Provider>, Asserter) {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        (
+            GpgWalletClient::new(provider, Network::tea_sepolia()),
+            asserter,
+        )
+    }
+
+    fn push_prediction(asserter: &Asserter, is_deployed: bool) {
+        asserter.push_success(&Bytes::from((WALLET, is_deployed).abi_encode_params()));
+    }
+
+    #[tokio::test]
+    async fn test_predict_address() -> Result<()> {
+        let (client, asserter) = mocked_client();
+        push_prediction(&asserter, true);
+        let prediction = client.predict_address(KEY_ID).await?;
+        assert_eq!(prediction.walletAddress, WALLET);
+        assert!(prediction.isDeployed);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_key_id_balance() -> Result<()> {
+        let (client, asserter) = mocked_client();
+        push_prediction(&asserter, true);
+        asserter.push_success(&U256::from(42));
+        assert_eq!(client.get_key_id_balance(KEY_ID).await?, U256::from(42));
+
+        push_prediction(&asserter, false);
+        assert!(matches!(
+            client.get_key_id_balance(KEY_ID).await,
+            Err(Error::NotDeployed(_))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_withdraw_signable_hash() -> Result<()> {
+        let (client, asserter) = mocked_client();
+        let to = "0x590b78eaF98053eFBa4107Eed2e0F70D2B90A45d";
+        let deadline = U256::from(1_700_000_000);
+        let hash = struct_hash(
+            &wallet_domain(client.network().chain_id, WALLET),
+            &SigningData {
+                blob: FixedBytes::ZERO,
+                wallet: WALLET,
+                deadline,
+                operation: Operation::Execute,
+                to: to.parse().expect("Invalid address"),
+                value: U256::from(400),
+                data: Bytes::new(),
+                paymaster_fee: U256::ZERO,
+                nonce: U256::from(7),
+            },
+        );
+        let push_execute = |hash: FixedBytes<32>| {
+            push_prediction(&asserter, true);
+            asserter.push_success(&U256::from(1000));
+            asserter.push_success(&Bytes::from(U256::from(7).abi_encode()));
+            asserter.push_success(&Bytes::from(hash.abi_encode()));
+        };
+        push_execute(hash);
+        let signing_data = client
+            .get_execute_signable_hash_with_deadline(
+                KEY_ID,
+                to,
+                U256::from(400),
+                Bytes::new(),
+                U256::ZERO,
+                deadline,
+            )
+            .await?;
+        assert_eq!(signing_data.blob, hash);
+        assert_eq!(signing_data.wallet, WALLET);
+        assert_eq!(signing_data.operation, Operation::Execute);
+        assert_eq!(signing_data.value, U256::from(400));
+        assert_eq!(signing_data.nonce, U256::from(7));
+        assert!(signing_data.data.is_empty());
+
+        // An RPC endpoint reporting some other hash to sign
+        push_execute(FixedBytes::repeat_byte(0xab));
+        assert!(matches!(
+            client
+                .get_withdraw_signable_hash(KEY_ID, to, U256::from(400), U256::ZERO)
+                .await,
+            Err(Error::StructHashMismatch { .. })
+        ));
+
+        push_prediction(&asserter, true);
+        asserter.push_success(&U256::from(1000));
+        assert!(matches!(
+            client
+                .get_withdraw_signable_hash(KEY_ID, to, U256::from(1001), U256::ZERO)
+                .await,
+            Err(Error::InsufficientFunds(_))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_paymaster_fee() -> Result<()> {
+        let (client, asserter) = mocked_client();
+        asserter.push_success(&U256::from(10));
+        assert_eq!(
+            client.suggest_paymaster_fee(100_000).await?,
+            U256::from(1_200_000)
+        );
+
+        let to = "0x590b78eaF98053eFBa4107Eed2e0F70D2B90A45d";
+        let deadline = U256::from(1_700_000_000);
+        let hash = struct_hash(
+            &wallet_domain(client.network().chain_id, WALLET),
+            &SigningData {
+                blob: FixedBytes::ZERO,
+                wallet: WALLET,
+                deadline,
+                operation: Operation::WithdrawAll,
+                to: to.parse().expect("Invalid address"),
+                value: U256::ZERO,
+                data: Bytes::new(),
+                paymaster_fee: U256::from(50),
+                nonce: U256::from(3),
+            },
+        );
+        push_prediction(&asserter, true);
+        asserter.push_success(&U256::from(1000));
+        asserter.push_success(&Bytes::from(U256::from(3).abi_encode()));
+        asserter.push_success(&Bytes::from(hash.abi_encode()));
+        let signing_data = client
+            .get_signable_hash_with_deadline(KEY_ID, to, U256::from(50), deadline)
+            .await?;
+        assert_eq!(signing_data.blob, hash);
+        assert_eq!(signing_data.operation, Operation::WithdrawAll);
+        assert_eq!(signing_data.paymaster_fee, U256::from(50));
+
+        push_prediction(&asserter, true);
+        asserter.push_success(&U256::from(1000));
+        assert!(matches!(
+            client
+                .get_withdraw_signable_hash(KEY_ID, to, U256::from(960), U256::from(50))
+                .await,
+            Err(Error::InsufficientFunds(_))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_submit_checks_struct_hash() -> Result<()> {
+        let (client, asserter) = mocked_client();
+        let to = "0x590b78eaF98053eFBa4107Eed2e0F70D2B90A45d";
+        let deadline = U256::from(unix_now() + 60);
+        // An RPC endpoint reporting a struct hash that isn't what the fields hash to
+        let push_wrong_hash = || {
+            push_prediction(&asserter, true);
+            asserter.push_success(&Bytes::from(U256::from(7).abi_encode()));
+            asserter.push_success(&Bytes::from(FixedBytes::<32>::ZERO.abi_encode()));
+        };
+
+        push_wrong_hash();
+        assert!(matches!(
+            client
+                .sweep_gpg_key(KEY_ID, to, U256::from(50), deadline, "0xabcd", "0x1234")
+                .await,
+            Err(Error::StructHashMismatch { .. })
+        ));
+        // Nothing else was asked for: no key ID lookup, call or broadcast
+        assert!(asserter.read_q().is_empty());
+
+        push_wrong_hash();
+        let signing_data = SigningData {
+            blob: FixedBytes::ZERO,
+            wallet: WALLET,
+            deadline,
+            operation: Operation::Execute,
+            to: to.parse().expect("Invalid address"),
+            value: U256::from(400),
+            data: Bytes::new(),
+            paymaster_fee: U256::from(50),
+            nonce: U256::from(7),
+        };
+        assert!(matches!(
+            client
+                .execute_with_sig(KEY_ID, &signing_data, "0xabcd", "0x1234")
+                .await,
+            Err(Error::StructHashMismatch { .. })
+        ));
+        assert!(asserter.read_q().is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_chain_id() -> Result<()> {
+        let (client, asserter) = mocked_client();
+        asserter.push_success(&U64::from(client.network().chain_id));
+        client.verify_chain_id().await?;
+        asserter.push_success(&U64::from(1));
+        assert!(matches!(
+            client.verify_chain_id().await,
+            Err(Error::ChainIdMismatch { actual: 1, .. })
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,400 @@
+use crate::{
+    Error, Result,
+    network::{DEFAULT_NETWORK, Network, NetworkEntry, builtin_networks},
+};
+use alloy::primitives::Address;
+use reqwest::Url as URL;
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    env, fmt, fs,
+    path::{Path, PathBuf},
+};
+
+pub const CONFIG_ENV: &str = "TEA_GPG_WALLET_CONFIG";
+pub const NETWORK_ENV: &str = "TEA_GPG_WALLET_NETWORK";
+pub const RPC_URL_ENV: &str = "TEA_GPG_WALLET_RPC_URL";
+pub const DEPLOYER_ENV: &str = "TEA_GPG_WALLET_DEPLOYER";
+
+/// Where an effective configuration value came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Default,
+    Profile(String),
+    File(PathBuf),
+    Env(&'static str),
+    Flag,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Default => write!(f, "built-in default"),
+            Source::Profile(name) => write!(f, "network profile {name}"),
+            Source::File(path) => write!(f, "config file {}", path.display()),
+            Source::Env(var) => write!(f, "environment variable {var}"),
+            Source::Flag => write!(f, "command line flag"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Setting<T> {
+    pub value: T,
+    pub source: Source,
+}
+
+/// Values supplied on the command line, highest precedence
+#[derive(Debug, Clone, Default)]
+pub struct Overrides {
+    pub config_path: Option<PathBuf>,
+    pub network: Option<String>,
+    pub rpc_url: Option<String>,
+    pub deployer: Option<String>,
+}
+
+// On-disk TOML layout, e.g.
+//   network = "local"
+//   rpc_url = "http://127.0.0.1:8545"  # overrides the profile selected here or by default
+//
+//   [networks.local]
+//   chain_id = 31337
+//   ...
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileConfig {
+    network: Option<String>,
+    rpc_url: Option<String>,
+    deployer: Option<String>,
+    #[serde(default)]
+    networks: BTreeMap<String, NetworkEntry>,
+}
+
+/// Effective runtime configuration, layered as
+/// command line flag > environment variable > config file > network profile
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub config_path: Option<PathBuf>,
+    /// The selected profile, with any RPC URL or deployer override applied
+    pub network: Setting<Network>,
+    pub rpc_url: Setting<URL>,
+    pub deployer: Setting<Address>,
+    /// Every known profile, built-in and from the config file
+    pub networks: BTreeMap<String, Network>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config::resolve(None, FileConfig::default(), |_| None, &Overrides::default())
+            .expect("Invalid built-in network profile")
+    }
+}
+
+impl Config {
+    /// Loads the configuration from the default locations, applying `overrides` last
+    pub fn load(overrides: &Overrides) -> Result<Config> {
+        let path = match &overrides.config_path {
+            Some(path) => Some(path.clone()),
+            None => env::var_os(CONFIG_ENV)
+                .map(PathBuf::from)
+                .or_else(default_config_path),
+        };
+        // An explicitly requested file must exist; the default location is optional
+        let explicit = overrides.config_path.is_some() || env::var_os(CONFIG_ENV).is_some();
+        let file = match &path {
+            Some(path) if explicit || path.exists() => Some(read_config_file(path)?),
+            _ => None,
+        };
+        Self::resolve(
+            path.filter(|_| file.is_some()),
+            file.unwrap_or_default(),
+            |var| env::var(var).ok().filter(|v| !v.is_empty()),
+            overrides,
+        )
+    }
+
+    fn resolve(
+        path: Option<PathBuf>,
+        file: FileConfig,
+        env_var: impl Fn(&'static str) -> Option<String>,
+        overrides: &Overrides,
+    ) -> Result<Config> {
+        let file_source = || Source::File(path.clone().unwrap_or_default());
+
+        let mut networks = builtin_networks();
+        for (name, entry) in file.networks {
+            let network = entry.into_network(&name)?;
+            networks.insert(name, network);
+        }
+
+        let mut name = Setting {
+            value: DEFAULT_NETWORK.to_string(),
+            source: Source::Default,
+        };
+        // An RPC URL or deployer override only applies to a network selected
+        // at the same or a lower layer, so e.g. `--network local` ignores a
+        // `TEA_GPG_WALLET_RPC_URL` meant for the default network
+        let mut name_layer = 0;
+        let layers = [
+            (file.network, file_source()),
+            (env_var(NETWORK_ENV), Source::Env(NETWORK_ENV)),
+            (overrides.network.clone(), Source::Flag),
+        ];
+        for (layer, (value, source)) in (1..).zip(layers) {
+            if let Some(value) = value {
+                name = Setting { value, source };
+                name_layer = layer;
+            }
+        }
+        let mut network = networks.get(&name.value).cloned().ok_or_else(|| {
+            Error::Config(format!(
+                "Unknown network {} from {} (known networks: {})",
+                name.value,
+                name.source,
+                networks.keys().cloned().collect::<Vec<_>>().join(", ")
+            ))
+        })?;
+
+        let mut rpc_url = Setting {
+            value: network.rpc_url().clone(),
+            source: Source::Profile(network.name.clone()),
+        };
+        let layers = [
+            (file.rpc_url, file_source()),
+            (env_var(RPC_URL_ENV), Source::Env(RPC_URL_ENV)),
+            (overrides.rpc_url.clone(), Source::Flag),
+        ];
+        for (layer, (value, source)) in (1..).zip(layers) {
+            if let Some(value) = value.filter(|_| layer >= name_layer) {
+                rpc_url = Setting {
+                    value: value.parse().map_err(|e| {
+                        Error::Config(format!("Invalid RPC URL {value:?} from {source}: {e}"))
+                    })?,
+                    source,
+                };
+            }
+        }
+
+        let mut deployer = Setting {
+            value: network.deployer,
+            source: Source::Profile(network.name.clone()),
+        };
+        let layers = [
+            (file.deployer, file_source()),
+            (env_var(DEPLOYER_ENV), Source::Env(DEPLOYER_ENV)),
+            (overrides.deployer.clone(), Source::Flag),
+        ];
+        for (layer, (value, source)) in (1..).zip(layers) {
+            if let Some(value) = value.filter(|_| layer >= name_layer) {
+                deployer = Setting {
+                    value: value.parse().map_err(|e| {
+                        Error::Config(format!(
+                            "Invalid deployer address {value:?} from {source}: {e}"
+                        ))
+                    })?,
+                    source,
+                };
+            }
+        }
+
+        network.rpc_urls.retain(|url| url != &rpc_url.value);
+        network.rpc_urls.insert(0, rpc_url.value.clone());
+        network.deployer = deployer.value;
+
+        Ok(Config {
+            config_path: path,
+            network: Setting {
+                value: network,
+                source: name.source,
+            },
+            rpc_url,
+            deployer,
+            networks,
+        })
+    }
+}
+
+/// `$XDG_CONFIG_HOME/tea-gpg-wallet/config.toml`, falling back to `~/.config`
+pub fn default_config_path() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("tea-gpg-wallet").join("config.toml"))
+}
+
+/// `$XDG_CACHE_HOME/tea-gpg-wallet/implementations.toml`, falling back to `~/.cache`
+pub fn default_cache_path() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CACHE_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+    Some(base.join("tea-gpg-wallet").join("implementations.toml"))
+}
+
+fn read_config_file(path: &Path) -> Result<FileConfig> {
+    let contents = fs::read_to_string(path).map_err(|e| {
+        Error::Config(format!(
+            "Failed to read config file {}: {e}",
+            path.display()
+        ))
+    })?;
+    toml::from_str(&contents).map_err(|e| {
+        Error::Config(format!(
+            "Failed to parse config file {}: {e}",
+            path.display()
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::DEFAULT_RPC_URL;
+    use alloy::primitives::address;
+
+    fn no_env(_: &'static str) -> Option<String> {
+        None
+    }
+
+    #[test]
+    fn test_defaults() -> Result<()> {
+        let config = Config::resolve(None, FileConfig::default(), no_env, &Overrides::default())?;
+        assert_eq!(config.network.value, Network::tea_sepolia());
+        assert_eq!(config.network.source, Source::Default);
+        assert_eq!(config.rpc_url.value.as_str(), DEFAULT_RPC_URL);
+        assert_eq!(
+            config.rpc_url.source,
+            Source::Profile(DEFAULT_NETWORK.to_string())
+        );
+        assert_eq!(
+            config.deployer.value,
+            address!("0x1e67d22542bd2eAFff45BEA53BebDA73E7A231dd")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_precedence() -> Result<(), Box<dyn std::error::Error>> {
+        let path = PathBuf::from("/tmp/config.toml");
+        let file: FileConfig = toml::from_str(
+            r#"
+            rpc_url = "http://file.example/"
+            deployer = "0x0000000000000000000000000000000000000001"
+            "#,
+        )?;
+        let env_var = |var: &'static str| match var {
+            RPC_URL_ENV => Some("http://env.example/".to_string()),
+            _ => None,
+        };
+        let overrides = Overrides {
+            rpc_url: Some("http://flag.example/".to_string()),
+            ..Default::default()
+        };
+        let config = Config::resolve(Some(path.clone()), file, env_var, &overrides)?;
+        assert_eq!(config.rpc_url.value.as_str(), "http://flag.example/");
+        assert_eq!(config.rpc_url.source, Source::Flag);
+        assert_eq!(config.network.value.rpc_url(), &config.rpc_url.value);
+        assert_eq!(
+            config.deployer.value,
+            address!("0x0000000000000000000000000000000000000001")
+        );
+        assert_eq!(config.deployer.source, Source::File(path));
+        assert_eq!(config.network.value.deployer, config.deployer.value);
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_network() -> Result<(), Box<dyn std::error::Error>> {
+        let file: FileConfig = toml::from_str(
+            r#"
+            [networks.local]
+            chain_id = 31337
+            rpc_urls = ["http://127.0.0.1:8545", "http://localhost:8545"]
+            deployer = "0x5FbDB2315678afecb367f032d93F642f64180aa3"
+            "#,
+        )?;
+        let env_var = |var: &'static str| match var {
+            NETWORK_ENV => Some("local".to_string()),
+            _ => None,
+        };
+        let config = Config::resolve(None, file, env_var, &Overrides::default())?;
+        assert_eq!(config.network.value.name, "local");
+        assert_eq!(config.network.value.chain_id, 31337);
+        assert_eq!(config.network.source, Source::Env(NETWORK_ENV));
+        assert_eq!(config.network.value.rpc_urls.len(), 2);
+        assert_eq!(config.rpc_url.source, Source::Profile("local".to_string()));
+        assert!(config.networks.contains_key(DEFAULT_NETWORK));
+        Ok(())
+    }
+
+    #[test]
+    fn test_overrides_scoped_to_network_layer() -> Result<(), Box<dyn std::error::Error>> {
+        let path = PathBuf::from("/tmp/config.toml");
+        let file = || -> Result<FileConfig, toml::de::Error> {
+            toml::from_str(
+                r#"
+                rpc_url = "http://file.example/"
+                deployer = "0x0000000000000000000000000000000000000001"
+                "#,
+            )
+        };
+        let env_var = |var: &'static str| match var {
+            RPC_URL_ENV => Some("http://env.example/".to_string()),
+            _ => None,
+        };
+
+        // An explicitly selected network keeps its own RPC URL and deployer
+        let overrides = Overrides {
+            network: Some("local".to_string()),
+            ..Default::default()
+        };
+        let config = Config::resolve(Some(path.clone()), file()?, env_var, &overrides)?;
+        let local = &config.networks["local"];
+        assert_eq!(config.network.value, *local);
+        assert_eq!(config.rpc_url.source, Source::Profile("local".to_string()));
+        assert_eq!(config.deployer.value, local.deployer);
+
+        // Overrides at or above the network's layer still apply
+        let overrides = Overrides {
+            network: Some("local".to_string()),
+            rpc_url: Some("http://flag.example/".to_string()),
+            ..Default::default()
+        };
+        let config = Config::resolve(Some(path.clone()), file()?, env_var, &overrides)?;
+        assert_eq!(config.rpc_url.value.as_str(), "http://flag.example/");
+        assert_eq!(config.deployer.value, local.deployer);
+
+        let env_var = |var: &'static str| match var {
+            NETWORK_ENV => Some("local".to_string()),
+            DEPLOYER_ENV => Some("0x0000000000000000000000000000000000000002".to_string()),
+            _ => None,
+        };
+        let config = Config::resolve(Some(path), file()?, env_var, &Overrides::default())?;
+        assert_eq!(config.rpc_url.source, Source::Profile("local".to_string()));
+        assert_eq!(
+            config.deployer.value,
+            address!("0x0000000000000000000000000000000000000002")
+        );
+        assert_eq!(config.deployer.source, Source::Env(DEPLOYER_ENV));
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        let overrides = Overrides {
+            deployer: Some("not an address".to_string()),
+            ..Default::default()
+        };
+        assert!(Config::resolve(None, FileConfig::default(), no_env, &overrides).is_err());
+        let overrides = Overrides {
+            network: Some("nowhere".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            Config::resolve(None, FileConfig::default(), no_env, &overrides),
+            Err(Error::Config(_))
+        ));
+        assert!(toml::from_str::<FileConfig>("unknown = 1").is_err());
+    }
+}
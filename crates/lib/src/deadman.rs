@@ -0,0 +1,118 @@
+use alloy::{
+    primitives::{Address, TxHash, U256},
+    providers::Provider,
+};
+
+use crate::{Error, Result, client::GpgWalletClient, utils::unix_now};
+
+/// A wallet's dead man switch: once `trigger_date` passes, the wallet's admin
+/// can recover whatever it holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeadmanSwitch {
+    pub wallet: Address,
+    pub admin: Address,
+    /// Unix time the switch fires at
+    pub trigger_date: u64,
+}
+
+impl DeadmanSwitch {
+    pub fn is_triggered(&self, now: u64) -> bool {
+        now >= self.trigger_date
+    }
+
+    /// Seconds until the switch fires, zero once it has
+    pub fn remaining(&self, now: u64) -> u64 {
+        self.trigger_date.saturating_sub(now)
+    }
+}
+
+/// A mined `recoverPostDeadmanSwitch` call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Recovery {
+    pub tx_hash: TxHash,
+    /// Wei sent to the admin
+    pub amount: U256,
+}
+
+impl<P: Provider> GpgWalletClient<P> {
+    pub async fn deadman_switch(&self, key_id: &str) -> Result<DeadmanSwitch> {
+        let address = self.deployed_address(key_id).await?;
+        let wallet = self.wallet(address);
+        Ok(DeadmanSwitch {
+            wallet: address,
+            admin: wallet.admin().call().await?,
+            trigger_date: wallet.deadmanSwitchDate().call().await?.saturating_to(),
+        })
+    }
+
+    /// Recovers the wallet's funds to its admin; the signer must be the admin
+    ///
+    /// Like `execute_with_sig`, the call is simulated first to learn the
+    /// amount it returns.
+    pub async fn recover_post_deadman_switch(&self, key_id: &str) -> Result<Recovery> {
+        let switch = self.deadman_switch(key_id).await?;
+        if !switch.is_triggered(unix_now()) {
+            return Err(Error::DeadmanSwitchNotTriggered);
+        }
+        let wallet = self.wallet(switch.wallet);
+        let call = wallet.recoverPostDeadmanSwitch();
+        let amount = call.call().await?;
+        let receipt = self
+            .send_transaction(call.into_transaction_request())
+            .await?;
+        Ok(Recovery {
+            tx_hash: receipt.transaction_hash,
+            amount,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::Network;
+    use alloy::{
+        primitives::{Bytes, address},
+        providers::{ProviderBuilder, mock::Asserter},
+        sol_types::SolValue,
+    };
+
+    const KEY_ID: &str = "95469C7E3DFC90B1";
+    const WALLET: Address = address!("0xd7baae85d719c2e8e27a70194471ef4b6b253d33");
+    const ADMIN: Address = address!("0x590b78eaF98053eFBa4107Eed2e0F70D2B90A45d");
+
+    fn push_switch(asserter: &Asserter, trigger_date: u64) {
+        asserter.push_success(&Bytes::from((WALLET, true).abi_encode_params()));
+        asserter.push_success(&Bytes::from(ADMIN.abi_encode()));
+        asserter.push_success(&Bytes::from(U256::from(trigger_date).abi_encode()));
+    }
+
+    #[tokio::test]
+    async fn test_deadman_switch() -> Result<()> {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        let client = GpgWalletClient::new(provider, Network::tea_sepolia());
+
+        push_switch(&asserter, 1_000);
+        let switch = client.deadman_switch(KEY_ID).await?;
+        assert_eq!(
+            switch,
+            DeadmanSwitch {
+                wallet: WALLET,
+                admin: ADMIN,
+                trigger_date: 1_000,
+            }
+        );
+        assert!(!switch.is_triggered(999));
+        assert_eq!(switch.remaining(400), 600);
+        assert!(switch.is_triggered(1_000));
+        assert_eq!(switch.remaining(2_000), 0);
+
+        push_switch(&asserter, unix_now() + 3_600);
+        assert!(matches!(
+            client.recover_post_deadman_switch(KEY_ID).await,
+            Err(Error::DeadmanSwitchNotTriggered)
+        ));
+        Ok(())
+    }
+}
@@ -1,12 +1,11 @@
-use crate::utils::{get_rpc_url, key_id_to_bytes};
+use crate::{Error, Result, client::GpgWalletClient, network::Network, utils::key_id_to_bytes};
 use alloy::{
-    primitives::{Address, U256},
-    providers::{Provider, ProviderBuilder},
-    rpc::types::TransactionRequest,
-    signers::local::PrivateKeySigner,
+    hex,
+    primitives::{Address, B256, Bytes, U256, keccak256},
     sol,
 };
-use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::Path};
 
 sol!(
     #[sol(rpc)]
@@ -14,59 +13,110 @@ sol!(
     "abi/GpgRewardDeployer.json"
 );
 
-pub fn get_contract_address() -> Result<Address> {
-    Address::parse_checksummed(env!("GPG_DEPLOYER_ADDRESS"), None)
-        .context("Invalid GPG deployer address configured at build time.")
+// EIP-1167 minimal proxy creation code, either side of the implementation address
+const CLONE_PREFIX: [u8; 20] = hex!("3d602d80600a3d3981f3363d3d373d3d3d363d73");
+const CLONE_SUFFIX: [u8; 15] = hex!("5af43d82803e903d91602b57fd5bf3");
+// Where the creation code's constructor ends and the deployed code begins
+const CLONE_RUNTIME_OFFSET: usize = 10;
+
+/// The code a wallet has once deployed: a minimal proxy to `implementation`
+pub fn wallet_runtime_code(implementation: Address) -> Bytes {
+    [
+        &CLONE_PREFIX[CLONE_RUNTIME_OFFSET..],
+        implementation.as_slice(),
+        &CLONE_SUFFIX[..],
+    ]
+    .concat()
+    .into()
+}
+
+/// Predicts a wallet address with no RPC calls, the way the deployer does:
+/// `Clones.predictDeterministicAddress(implementation, bytes32(keyId))`
+pub fn predict_address_offline(
+    deployer: Address,
+    implementation: Address,
+    key_id: &str,
+) -> Result<Address> {
+    let salt = B256::right_padding_from(key_id_to_bytes(key_id)?.as_slice());
+    let init_code = [
+        &CLONE_PREFIX[..],
+        implementation.as_slice(),
+        &CLONE_SUFFIX[..],
+    ]
+    .concat();
+    Ok(deployer.create2(salt, keccak256(init_code)))
+}
+
+/// Deployer implementations seen online, keyed by chain ID and deployer, so
+/// wallet addresses can be predicted later without an RPC endpoint
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ImplementationCache(BTreeMap<String, Address>);
+
+impl ImplementationCache {
+    /// Loads the cache at `path`; a missing file is an empty cache
+    pub fn load(path: &Path) -> Result<ImplementationCache> {
+        if !path.exists() {
+            return Ok(ImplementationCache::default());
+        }
+        let contents = std::fs::read_to_string(path).map_err(|e| cache_error(path, e))?;
+        toml::from_str(&contents).map_err(|e| cache_error(path, e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| cache_error(path, e))?;
+        }
+        let contents = toml::to_string(self).map_err(|e| cache_error(path, e))?;
+        std::fs::write(path, contents).map_err(|e| cache_error(path, e))
+    }
+
+    /// The network's configured implementation, or else the cached one
+    pub fn get(&self, network: &Network) -> Option<Address> {
+        network
+            .implementation
+            .or_else(|| self.0.get(&Self::key(network)).copied())
+    }
+
+    pub fn insert(&mut self, network: &Network, implementation: Address) {
+        self.0.insert(Self::key(network), implementation);
+    }
+
+    fn key(network: &Network) -> String {
+        format!("{}:{}", network.chain_id, network.deployer)
+    }
+}
+
+fn cache_error(path: &Path, err: impl std::fmt::Display) -> Error {
+    Error::Config(format!("Implementation cache {}: {err}", path.display()))
 }
 
-pub async fn predict_address(key_id: &str) -> Result<GpgRewardDeployer::predictAddressReturn> {
-    let provider = ProviderBuilder::new().connect_http(get_rpc_url()?);
-    let key_id = key_id_to_bytes(key_id)?;
-    GpgRewardDeployer::new(get_contract_address()?, provider)
-        .predictAddress(key_id)
-        .call()
+// The free functions below connect a fresh GpgWalletClient per call; build a
+// client once and reuse it when making more than one call.
+
+pub async fn predict_address(
+    network: &Network,
+    key_id: &str,
+) -> Result<GpgRewardDeployer::predictAddressReturn> {
+    GpgWalletClient::connect(network)
+        .predict_address(key_id)
         .await
-        .context("Failed to predict address")
 }
 
 pub async fn ensure_deployed(
+    network: &Network,
     key_id: &str,
     private_key: &str,
 ) -> Result<GpgRewardDeployer::predictAddressReturn> {
-    let prediction = predict_address(key_id).await?;
-
-    if prediction.isDeployed {
-        return Ok(prediction);
-    }
-    let provider = ProviderBuilder::new()
-        .wallet(
-            private_key
-                .parse::<PrivateKeySigner>()
-                .context("Invalid private key")?,
-        )
-        .connect_http(get_rpc_url()?);
-    GpgRewardDeployer::new(get_contract_address()?, provider)
-        .deploy_call(key_id_to_bytes(key_id)?)
-        .send()
+    GpgWalletClient::connect_with_signer(network, private_key)?
+        .ensure_deployed(key_id)
         .await
-        .context("Failed to deploy GPG reward wallet")?
-        .get_receipt()
-        .await
-        .context("Deployment transaction failed")?;
-
-    predict_address(key_id).await
 }
 
-pub async fn get_key_id_balance(key_id: &str) -> Result<U256> {
-    let provider = ProviderBuilder::new().connect_http(get_rpc_url()?);
-    let destination = predict_address(key_id).await?;
-    if !destination.isDeployed {
-        return Err(anyhow!("GPG wallet for key ID {key_id} is not deployed"));
-    }
-    provider
-        .get_balance(destination.walletAddress)
+pub async fn get_key_id_balance(network: &Network, key_id: &str) -> Result<U256> {
+    GpgWalletClient::connect(network)
+        .get_key_id_balance(key_id)
         .await
-        .context("Failed to get balance")
 }
 
 // send to a gpg wallet, confirming and deploying as necessary
@@ -74,36 +124,16 @@ pub async fn get_key_id_balance(key_id: &str) -> Result<U256> {
 // amount: the amount to send in wei
 // private_key: the private key of the sender
 // returns balance
-pub async fn send_to_gpg_key(key_id: &str, amount: U256, private_key: &str) -> Result<U256> {
-    let provider = ProviderBuilder::new()
-        .wallet(
-            private_key
-                .parse::<PrivateKeySigner>()
-                .context("Invalid private key")?,
-        )
-        .connect_http(get_rpc_url()?);
-    let destination = ensure_deployed(key_id, private_key).await?;
-
-    let send = TransactionRequest::default()
-        .to(destination.walletAddress)
-        .value(amount);
-
-    let receipt = provider
-        .send_transaction(send)
-        .await
-        .context("Failed to send transaction")?
-        .get_receipt()
+pub async fn send_to_gpg_key(
+    network: &Network,
+    key_id: &str,
+    amount: U256,
+    private_key: &str,
+) -> Result<U256> {
+    GpgWalletClient::connect_with_signer(network, private_key)?
+        .send_to_gpg_key(key_id, amount)
         .await
-        .context("Transaction failed")?;
-
-    if !receipt.status() {
-        return Err(anyhow!(
-            "Transaction failed with status: {:?}",
-            receipt.status()
-        ));
-    }
-
-    get_key_id_balance(key_id).await
+        .map(|transfer| transfer.new_balance)
 }
 
 #[cfg(test)]
@@ -113,11 +143,11 @@ mod tests {
     use alloy::primitives::address;
 
     use super::*;
-    use anyhow::Result;
 
     #[tokio::test]
     async fn test_predict_address() -> Result<()> {
-        let predicted_address = predict_address("95469C7E3DFC90B1").await?;
+        let predicted_address =
+            predict_address(&Network::tea_sepolia(), "95469C7E3DFC90B1").await?;
         assert_eq!(
             predicted_address.walletAddress,
             address!("0xd7baae85d719c2e8e27a70194471ef4b6b253d33")
@@ -125,6 +155,77 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_predict_address_offline_matches_deployer() -> Result<()> {
+        let network = Network::tea_sepolia();
+        let implementation = GpgWalletClient::connect(&network).implementation().await?;
+        assert_eq!(
+            predict_address_offline(network.deployer, implementation, "95469C7E3DFC90B1")?,
+            address!("0xd7baae85d719c2e8e27a70194471ef4b6b253d33")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_predict_address_offline() -> Result<()> {
+        // CREATE2 spelled out: keccak256(0xff ++ deployer ++ salt ++ keccak256(init code))
+        let deployer = address!("0x1e67d22542bd2eAFff45BEA53BebDA73E7A231dd");
+        let implementation = address!("0x590b78eaF98053eFBa4107Eed2e0F70D2B90A45d");
+        let init_code = hex!(
+            "3d602d80600a3d3981f3363d3d373d3d3d363d73590b78eaf98053efba4107eed2e0f70d2b90a45d5af43d82803e903d91602b57fd5bf3"
+        );
+        let salt = hex!("95469c7e3dfc90b1000000000000000000000000000000000000000000000000");
+        let hash =
+            keccak256([&[0xff], &deployer[..], &salt[..], &keccak256(init_code)[..]].concat());
+        assert_eq!(
+            predict_address_offline(deployer, implementation, "95469C7E3DFC90B1")?,
+            Address::from_slice(&hash[12..])
+        );
+        assert_ne!(
+            predict_address_offline(deployer, implementation, "95469C7E3DFC90B2")?,
+            Address::from_slice(&hash[12..])
+        );
+        assert!(predict_address_offline(deployer, implementation, "not a key").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_wallet_runtime_code() {
+        let implementation = address!("0x590b78eaF98053eFBa4107Eed2e0F70D2B90A45d");
+        assert_eq!(
+            wallet_runtime_code(implementation)[..],
+            hex!(
+                "363d3d373d3d3d363d73590b78eaf98053efba4107eed2e0f70d2b90a45d5af43d82803e903d91602b57fd5bf3"
+            )
+        );
+    }
+
+    #[test]
+    fn test_implementation_cache() -> Result<()> {
+        let path = std::env::temp_dir()
+            .join(format!("implementation-cache-{}", std::process::id()))
+            .join("implementations.toml");
+        let mut network = Network::tea_sepolia();
+        let implementation = address!("0x590b78eaF98053eFBa4107Eed2e0F70D2B90A45d");
+
+        let mut cache = ImplementationCache::load(&path)?;
+        assert_eq!(cache.get(&network), None);
+        cache.insert(&network, implementation);
+        cache.save(&path)?;
+        let loaded = ImplementationCache::load(&path);
+        std::fs::remove_dir_all(path.parent().expect("No parent")).ok();
+        let loaded = loaded?;
+        assert_eq!(loaded.get(&network), Some(implementation));
+
+        // A configured implementation wins over the cache
+        network.implementation = Some(Address::ZERO);
+        assert_eq!(loaded.get(&network), Some(Address::ZERO));
+        network.implementation = None;
+        network.deployer = Address::ZERO;
+        assert_eq!(loaded.get(&network), None);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_ensure_deployed() -> Result<()> {
         let Ok(pk) = env::var("PRIVATE_KEY") else {
@@ -133,7 +234,7 @@ mod tests {
         };
         // already deployed
         let key_id = "95469C7E3DFC90B1";
-        let prediction = ensure_deployed(key_id, &pk).await?;
+        let prediction = ensure_deployed(&Network::tea_sepolia(), key_id, &pk).await?;
         assert!(prediction.isDeployed);
         assert_eq!(
             prediction.walletAddress,
@@ -144,7 +245,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_key_id_balance() -> Result<()> {
-        let balance = get_key_id_balance("95469C7E3DFC90B1").await?;
+        let balance = get_key_id_balance(&Network::tea_sepolia(), "95469C7E3DFC90B1").await?;
         eprintln!("Balance: {balance}");
         Ok(())
     }
@@ -158,8 +259,9 @@ mod tests {
         let key_id = "95469C7E3DFC90B1";
         let amount = U256::from(1_000_000_000_000_000u64); // 1 Gwei
 
-        let starting_balance = get_key_id_balance(key_id).await?;
-        let new_balance = send_to_gpg_key(key_id, amount, &pk).await?;
+        let network = Network::tea_sepolia();
+        let starting_balance = get_key_id_balance(&network, key_id).await?;
+        let new_balance = send_to_gpg_key(&network, key_id, amount, &pk).await?;
         assert_eq!(new_balance, starting_balance + amount);
         Ok(())
     }
@@ -0,0 +1,200 @@
+use alloy::{
+    primitives::{Address, FixedBytes, U256, keccak256},
+    sol,
+    sol_types::{Eip712Domain, SolStruct},
+};
+
+use crate::{
+    Error, Result,
+    wallet::{Operation, SigningData},
+};
+
+/// The wallet's EIP-712 domain name, as `eip712Domain()` reports it
+pub const DOMAIN_NAME: &str = "GpgRewardWallet";
+/// The wallet's EIP-712 domain version, as `eip712Domain()` reports it
+pub const DOMAIN_VERSION: &str = "1";
+
+sol! {
+    /// Signed by `withdrawAll`; see `WITHDRAW_ALL_TYPEHASH`
+    struct WithdrawAll {
+        address to;
+        uint256 paymasterFee;
+        uint256 deadline;
+        uint256 nonce;
+    }
+
+    /// Signed by `executeWithSig`; see `EXECUTE_TYPEHASH`
+    struct Execute {
+        address to;
+        uint256 value;
+        bytes data;
+        uint256 paymasterFee;
+        uint256 deadline;
+        uint256 nonce;
+    }
+}
+
+/// What the wallet's `WITHDRAW_ALL_TYPEHASH` should be
+pub fn withdraw_all_typehash() -> FixedBytes<32> {
+    keccak256(WithdrawAll::eip712_encode_type().as_bytes())
+}
+
+/// What the wallet's `EXECUTE_TYPEHASH` should be
+pub fn execute_typehash() -> FixedBytes<32> {
+    keccak256(Execute::eip712_encode_type().as_bytes())
+}
+
+/// The domain of the wallet at `wallet` on `chain_id`, needing no RPC calls
+pub fn wallet_domain(chain_id: u64, wallet: Address) -> Eip712Domain {
+    Eip712Domain::new(
+        Some(DOMAIN_NAME.into()),
+        Some(DOMAIN_VERSION.into()),
+        Some(U256::from(chain_id)),
+        Some(wallet),
+        None,
+    )
+}
+
+/// The EIP-712 signing hash of `signing_data`'s operation under `domain`,
+/// which is what `getWithdrawAllStructHash` and `getExecuteStructHash`
+/// return; `signing_data.blob` is ignored
+pub fn struct_hash(domain: &Eip712Domain, signing_data: &SigningData) -> FixedBytes<32> {
+    match signing_data.operation {
+        Operation::WithdrawAll => WithdrawAll {
+            to: signing_data.to,
+            paymasterFee: signing_data.paymaster_fee,
+            deadline: signing_data.deadline,
+            nonce: signing_data.nonce,
+        }
+        .eip712_signing_hash(domain),
+        Operation::Execute => Execute {
+            to: signing_data.to,
+            value: signing_data.value,
+            data: signing_data.data.clone(),
+            paymasterFee: signing_data.paymaster_fee,
+            deadline: signing_data.deadline,
+            nonce: signing_data.nonce,
+        }
+        .eip712_signing_hash(domain),
+    }
+}
+
+/// Fails unless `signing_data.blob`, as the contract (or RPC endpoint)
+/// reported it, is the hash computed locally
+pub fn check_struct_hash(domain: &Eip712Domain, signing_data: &SigningData) -> Result<()> {
+    let computed = struct_hash(domain, signing_data);
+    if computed != signing_data.blob {
+        return Err(Error::StructHashMismatch {
+            computed,
+            reported: signing_data.blob,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{
+        primitives::{Bytes, address},
+        sol_types::SolValue,
+    };
+
+    const WALLET: Address = address!("0xd7baae85d719c2e8e27a70194471ef4b6b253d33");
+
+    fn signing_data(operation: Operation) -> SigningData {
+        SigningData {
+            blob: FixedBytes::ZERO,
+            wallet: WALLET,
+            deadline: U256::from(1_700_000_000),
+            operation,
+            to: address!("0x590b78eaF98053eFBa4107Eed2e0F70D2B90A45d"),
+            value: U256::from(400),
+            data: Bytes::from_static(&[0xde, 0xad]),
+            paymaster_fee: U256::from(1000),
+            nonce: U256::from(7),
+        }
+    }
+
+    // Spelled out by hand, as the contract does it
+    fn expected(domain_separator: FixedBytes<32>, struct_hash: FixedBytes<32>) -> FixedBytes<32> {
+        keccak256([&[0x19, 0x01], &domain_separator[..], &struct_hash[..]].concat())
+    }
+
+    #[test]
+    fn test_struct_hash() {
+        let domain = wallet_domain(10218, WALLET);
+        let domain_separator = keccak256(
+            (
+                keccak256(
+                    "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)",
+                ),
+                keccak256(DOMAIN_NAME),
+                keccak256(DOMAIN_VERSION),
+                U256::from(10218),
+                WALLET,
+            )
+                .abi_encode(),
+        );
+        assert_eq!(domain.separator(), domain_separator);
+
+        let data = signing_data(Operation::WithdrawAll);
+        assert_eq!(
+            withdraw_all_typehash(),
+            keccak256(
+                "WithdrawAll(address to,uint256 paymasterFee,uint256 deadline,uint256 nonce)"
+            )
+        );
+        let withdraw_all = keccak256(
+            (
+                withdraw_all_typehash(),
+                data.to,
+                data.paymaster_fee,
+                data.deadline,
+                data.nonce,
+            )
+                .abi_encode(),
+        );
+        assert_eq!(
+            struct_hash(&domain, &data),
+            expected(domain_separator, withdraw_all)
+        );
+
+        let data = signing_data(Operation::Execute);
+        assert_eq!(
+            execute_typehash(),
+            keccak256(
+                "Execute(address to,uint256 value,bytes data,uint256 paymasterFee,uint256 deadline,uint256 nonce)"
+            )
+        );
+        let execute = keccak256(
+            (
+                execute_typehash(),
+                data.to,
+                data.value,
+                keccak256(&data.data),
+                data.paymaster_fee,
+                data.deadline,
+                data.nonce,
+            )
+                .abi_encode(),
+        );
+        assert_eq!(
+            struct_hash(&domain, &data),
+            expected(domain_separator, execute)
+        );
+    }
+
+    #[test]
+    fn test_check_struct_hash() {
+        let domain = wallet_domain(10218, WALLET);
+        let mut data = signing_data(Operation::WithdrawAll);
+        data.blob = struct_hash(&domain, &data);
+        assert!(check_struct_hash(&domain, &data).is_ok());
+        // Same message, but for another chain
+        assert!(matches!(
+            check_struct_hash(&wallet_domain(1, WALLET), &data),
+            Err(Error::StructHashMismatch { .. })
+        ));
+    }
+}
@@ -0,0 +1,213 @@
+use crate::wallet::GpgRewardWallet::GpgRewardWalletErrors;
+use alloy::{
+    contract,
+    primitives::{Bytes, FixedBytes, TxHash, U256},
+    providers::PendingTransactionError,
+    sol_types::decode_revert_reason,
+    transports::{RpcError, TransportError},
+};
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Everything that can go wrong talking to a GPG wallet
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("GPG wallet for key ID {0} is not deployed")]
+    NotDeployed(String),
+    #[error("Invalid key ID {0:?}, expected 16 hex characters")]
+    InvalidKeyId(String),
+    #[error("Invalid address {0:?}")]
+    InvalidAddress(String),
+    #[error("Invalid amount {0:?}")]
+    InvalidAmount(String),
+    #[error("Invalid {what}: {reason}")]
+    InvalidInput { what: &'static str, reason: String },
+    #[error("Invalid private key")]
+    InvalidPrivateKey(#[source] alloy::signers::local::LocalSignerError),
+    #[error("Configuration error: {0}")]
+    Config(String),
+    #[error("RPC endpoint is on chain {actual}, but network {network} expects chain {expected}")]
+    ChainIdMismatch {
+        network: String,
+        expected: u64,
+        actual: u64,
+    },
+    #[error("RPC transport failure: {0}")]
+    Transport(#[source] TransportError),
+    #[error("Signature rejected: {0}")]
+    SignatureRejected(String),
+    #[error(
+        "Struct hash mismatch: computed {computed} locally, but the contract reported {reported}"
+    )]
+    StructHashMismatch {
+        computed: FixedBytes<32>,
+        reported: FixedBytes<32>,
+    },
+    #[error("Signature deadline expired: {0}")]
+    DeadlineExpired(String),
+    #[error("Insufficient funds: {0}")]
+    InsufficientFunds(String),
+    #[error("Paymaster fee of {offered} wei doesn't cover the estimated {required} wei of gas")]
+    PaymasterFeeTooLow { offered: U256, required: U256 },
+    #[error("Estimated fee of {estimated} wei exceeds the maximum of {limit} wei")]
+    FeeTooHigh { estimated: U256, limit: U256 },
+    #[error("Dead man switch already triggered")]
+    DeadmanSwitchAlreadyTriggered,
+    #[error("Dead man switch not triggered yet")]
+    DeadmanSwitchNotTriggered,
+    #[error("String too long: {0:?}")]
+    StringTooLong(String),
+    #[error("Invalid short string")]
+    InvalidShortString,
+    #[error("Contract reverted: {}", reason.as_deref().unwrap_or("no reason given"))]
+    Reverted {
+        reason: Option<String>,
+        data: Option<Bytes>,
+    },
+    #[error("Chain reorganized past confirmed block {0}; try more confirmations")]
+    Reorg(u64),
+    #[error("Transaction {0} reverted")]
+    TransactionReverted(TxHash),
+    #[error("Failed waiting for transaction: {0}")]
+    PendingTransaction(#[source] PendingTransactionError),
+    #[error("Contract interaction failed: {0}")]
+    Contract(#[source] contract::Error),
+    #[error("OpenPGP error: {0}")]
+    OpenPgp(String),
+    #[error("Relayer error: {0}")]
+    Relayer(String),
+    #[error("Relayer request failed: {0}")]
+    Http(#[from] reqwest::Error),
+}
+
+impl Error {
+    /// Classifies a revert by its reason string, since the verifier and
+    /// deadline checks revert with messages rather than custom errors
+    fn from_revert(reason: Option<String>, data: Option<Bytes>) -> Self {
+        let lower = reason.as_deref().unwrap_or_default().to_lowercase();
+        match reason {
+            Some(reason) if lower.contains("signature") => Error::SignatureRejected(reason),
+            Some(reason) if lower.contains("deadline") || lower.contains("expired") => {
+                Error::DeadlineExpired(reason)
+            }
+            Some(reason) if lower.contains("insufficient") => Error::InsufficientFunds(reason),
+            reason => Error::Reverted { reason, data },
+        }
+    }
+}
+
+impl From<GpgRewardWalletErrors> for Error {
+    fn from(err: GpgRewardWalletErrors) -> Self {
+        match err {
+            GpgRewardWalletErrors::DeadmanSwitchAlreadyTriggered(_) => {
+                Error::DeadmanSwitchAlreadyTriggered
+            }
+            GpgRewardWalletErrors::DeadmanSwitchNotTriggered(_) => Error::DeadmanSwitchNotTriggered,
+            GpgRewardWalletErrors::StringTooLong(e) => Error::StringTooLong(e.str),
+            GpgRewardWalletErrors::InvalidShortString(_) => Error::InvalidShortString,
+        }
+    }
+}
+
+impl From<TransportError> for Error {
+    fn from(err: TransportError) -> Self {
+        if let RpcError::ErrorResp(resp) = &err {
+            if let Some(data) = resp.as_revert_data() {
+                return revert_data_to_error(data);
+            }
+            let message = resp.message.to_string();
+            if message.to_lowercase().contains("insufficient funds") {
+                return Error::InsufficientFunds(message);
+            }
+            if let Some(reason) = message.strip_prefix("execution reverted") {
+                let reason = reason.trim_start_matches(':').trim();
+                return Error::from_revert((!reason.is_empty()).then(|| reason.to_string()), None);
+            }
+        }
+        Error::Transport(err)
+    }
+}
+
+impl From<contract::Error> for Error {
+    fn from(err: contract::Error) -> Self {
+        match err {
+            contract::Error::TransportError(err) => err.into(),
+            contract::Error::PendingTransactionError(err) => err.into(),
+            err => Error::Contract(err),
+        }
+    }
+}
+
+impl From<PendingTransactionError> for Error {
+    fn from(err: PendingTransactionError) -> Self {
+        match err {
+            PendingTransactionError::TransportError(err) => err.into(),
+            err => Error::PendingTransaction(err),
+        }
+    }
+}
+
+fn revert_data_to_error(data: Bytes) -> Error {
+    use alloy::sol_types::SolInterface;
+    if let Ok(decoded) = GpgRewardWalletErrors::abi_decode(&data) {
+        return decoded.into();
+    }
+    Error::from_revert(decode_revert_reason(&data), Some(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wallet::GpgRewardWallet;
+    use alloy::{rpc::json_rpc::ErrorPayload, sol_types::SolError};
+
+    fn revert(data: Option<Bytes>, message: &str) -> Error {
+        let payload = ErrorPayload {
+            code: 3,
+            message: message.to_string().into(),
+            data: data.map(|data| {
+                serde_json::value::to_raw_value(&data).expect("Failed to encode revert data")
+            }),
+        };
+        TransportError::ErrorResp(payload).into()
+    }
+
+    #[test]
+    fn test_decodes_custom_errors() {
+        let data = GpgRewardWallet::DeadmanSwitchAlreadyTriggered {}.abi_encode();
+        assert!(matches!(
+            revert(Some(data.into()), "execution reverted"),
+            Error::DeadmanSwitchAlreadyTriggered
+        ));
+        let data = GpgRewardWallet::StringTooLong {
+            str: "too long".to_string(),
+        }
+        .abi_encode();
+        assert!(matches!(
+            revert(Some(data.into()), "execution reverted"),
+            Error::StringTooLong(s) if s == "too long"
+        ));
+    }
+
+    #[test]
+    fn test_classifies_revert_reasons() {
+        let data = alloy::sol_types::Revert::from("Invalid signature").abi_encode();
+        assert!(matches!(
+            revert(Some(data.into()), "execution reverted"),
+            Error::SignatureRejected(_)
+        ));
+        assert!(matches!(
+            revert(None, "execution reverted: Deadline expired"),
+            Error::DeadlineExpired(_)
+        ));
+        assert!(matches!(
+            revert(None, "insufficient funds for gas * price + value"),
+            Error::InsufficientFunds(_)
+        ));
+        assert!(matches!(
+            revert(None, "execution reverted"),
+            Error::Reverted { reason: None, .. }
+        ));
+        assert!(matches!(revert(None, "rate limited"), Error::Transport(_)));
+    }
+}
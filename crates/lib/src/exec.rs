@@ -0,0 +1,173 @@
+use alloy::{
+    dyn_abi::{DynSolValue, FunctionExt, JsonAbiExt, Specifier},
+    hex,
+    json_abi::Function,
+    primitives::Bytes,
+};
+
+use crate::{Error, Result, utils::parse_bytes};
+
+/// A contract call from a GPG wallet, built from a human-readable signature
+/// such as `approve(address,uint256)` or raw calldata
+#[derive(Debug, Clone)]
+pub struct ContractCall {
+    pub function: Option<Function>,
+    pub data: Bytes,
+}
+
+impl ContractCall {
+    /// Encodes `signature` with `args`; outputs may be given cast-style,
+    /// `balanceOf(address)(uint256)`, or as `balanceOf(address) returns (uint256)`
+    pub fn from_signature(signature: &str, args: &[impl AsRef<str>]) -> Result<ContractCall> {
+        let function = parse_function(signature)?;
+        if function.inputs.len() != args.len() {
+            return Err(Error::InvalidInput {
+                what: "arguments",
+                reason: format!(
+                    "{} expects {} arguments, got {}",
+                    function.signature(),
+                    function.inputs.len(),
+                    args.len()
+                ),
+            });
+        }
+        let values = function
+            .inputs
+            .iter()
+            .zip(args)
+            .map(|(param, arg)| {
+                param
+                    .resolve()
+                    .and_then(|ty| ty.coerce_str(arg.as_ref()))
+                    .map_err(|e| Error::InvalidInput {
+                        what: "argument",
+                        reason: format!("{:?} as {}: {e}", arg.as_ref(), param.ty),
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let data = function
+            .abi_encode_input(&values)
+            .map_err(|e| Error::InvalidInput {
+                what: "arguments",
+                reason: e.to_string(),
+            })?;
+        Ok(ContractCall {
+            function: Some(function),
+            data: data.into(),
+        })
+    }
+
+    /// Raw hex calldata; `outputs` is an optional signature used only to decode the result
+    pub fn from_calldata(calldata: &str, outputs: Option<&str>) -> Result<ContractCall> {
+        Ok(ContractCall {
+            function: outputs.map(parse_function).transpose()?,
+            data: parse_bytes("calldata", calldata)?,
+        })
+    }
+
+    /// Decodes the call's return data, if the signature declared outputs
+    pub fn decode_output(&self, returndata: &[u8]) -> Result<Option<Vec<DynSolValue>>> {
+        let Some(function) = self.function.as_ref().filter(|f| !f.outputs.is_empty()) else {
+            return Ok(None);
+        };
+        function
+            .abi_decode_output(returndata)
+            .map(Some)
+            .map_err(|e| Error::InvalidInput {
+                what: "return data",
+                reason: e.to_string(),
+            })
+    }
+}
+
+fn parse_function(signature: &str) -> Result<Function> {
+    Function::parse(signature).map_err(|e| Error::InvalidInput {
+        what: "function signature",
+        reason: e.to_string(),
+    })
+}
+
+/// Renders a decoded value the way it would be written as a call argument
+pub fn format_value(value: &DynSolValue) -> String {
+    match value {
+        DynSolValue::Bool(b) => b.to_string(),
+        DynSolValue::Int(i, _) => i.to_string(),
+        DynSolValue::Uint(u, _) => u.to_string(),
+        DynSolValue::FixedBytes(word, size) => hex::encode_prefixed(&word[..*size]),
+        DynSolValue::Address(address) => address.to_checksum(None),
+        DynSolValue::Function(function) => hex::encode_prefixed(function.as_slice()),
+        DynSolValue::Bytes(bytes) => hex::encode_prefixed(bytes),
+        DynSolValue::String(s) => format!("{s:?}"),
+        DynSolValue::Array(values) | DynSolValue::FixedArray(values) => format!(
+            "[{}]",
+            values
+                .iter()
+                .map(format_value)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        DynSolValue::Tuple(values) => format!(
+            "({})",
+            values
+                .iter()
+                .map(format_value)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{
+        primitives::{U256, address},
+        sol_types::SolValue,
+    };
+
+    #[test]
+    fn test_from_signature() -> Result<()> {
+        let call = ContractCall::from_signature(
+            "approve(address,uint256)(bool)",
+            &["0x590b78eaF98053eFBa4107Eed2e0F70D2B90A45d", "1000"],
+        )?;
+        assert_eq!(
+            call.data,
+            Bytes::from(hex!(
+                "095ea7b3000000000000000000000000590b78eaf98053efba4107eed2e0f70d2b90a45d00000000000000000000000000000000000000000000000000000000000003e8"
+            ))
+        );
+        let output = call.decode_output(&true.abi_encode())?;
+        assert_eq!(output, Some(vec![DynSolValue::Bool(true)]));
+
+        assert!(ContractCall::from_signature("approve(address,uint256)", &["0x00"]).is_err());
+        assert!(ContractCall::from_signature("approve(address)", &["nope"]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_calldata() -> Result<()> {
+        let call =
+            ContractCall::from_calldata("0x18160ddd", Some("totalSupply() returns (uint256)"))?;
+        assert_eq!(call.data, Bytes::from(hex!("18160ddd")));
+        let output = call.decode_output(&U256::from(5).abi_encode())?;
+        assert_eq!(output, Some(vec![DynSolValue::Uint(U256::from(5), 256)]));
+
+        let call = ContractCall::from_calldata("0x18160ddd", None)?;
+        assert_eq!(call.decode_output(&[])?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_value() {
+        let value = DynSolValue::Tuple(vec![
+            DynSolValue::Address(address!("0x590b78eaF98053eFBa4107Eed2e0F70D2B90A45d")),
+            DynSolValue::Array(vec![DynSolValue::Uint(U256::from(1), 256)]),
+            DynSolValue::String("tea".to_string()),
+        ]);
+        assert_eq!(
+            format_value(&value),
+            "(0x590b78eaF98053eFBa4107Eed2e0F70D2B90A45d, [1], \"tea\")"
+        );
+    }
+}
@@ -0,0 +1,540 @@
+use alloy::{
+    consensus::Transaction as _,
+    eips::BlockId,
+    network::TransactionResponse,
+    primitives::{Address, Bytes, TxHash, U256},
+    providers::Provider,
+    rpc::types::Block,
+    sol_types::SolCall,
+};
+use futures::{StreamExt, TryStreamExt, stream};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::{Error, Result, client::GpgWalletClient, wallet::GpgRewardWallet};
+
+/// Bumped when the checkpoint file layout changes incompatibly
+pub const HISTORY_CHECKPOINT_VERSION: u32 = 1;
+
+/// Blocks `history` fetches at once
+pub const HISTORY_CONCURRENCY: usize = 8;
+
+/// What a transaction did to the wallet's balance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HistoryKind {
+    /// A plain value transfer into the wallet
+    Deposit,
+    /// `withdrawAll(to, paymasterFee, deadline, pubKey, signature)`
+    WithdrawAll,
+    /// `executeWithSig(to, value, data, paymasterFee, deadline, pubKey, signature)`
+    Execute,
+    /// `recoverPostDeadmanSwitch()`, sending everything to the admin
+    Recovery,
+}
+
+impl std::fmt::Display for HistoryKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            HistoryKind::Deposit => "deposit",
+            HistoryKind::WithdrawAll => "withdrawAll",
+            HistoryKind::Execute => "executeWithSig",
+            HistoryKind::Recovery => "recovery",
+        })
+    }
+}
+
+/// One transaction that moved value into or out of a wallet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub block: u64,
+    pub timestamp: u64,
+    pub tx_hash: TxHash,
+    pub kind: HistoryKind,
+    /// The transaction's sender: the depositor, or whoever submitted a signed call
+    pub from: Address,
+    /// Where the value went: the wallet for deposits, the destination otherwise
+    pub to: Address,
+    /// Wei moved, paymaster fee excluded
+    pub amount: U256,
+    /// Wei paid from the wallet to the submitter
+    pub paymaster_fee: U256,
+    /// The signature nonce a signed call consumed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<U256>,
+    /// Calldata an `executeWithSig` forwarded
+    #[serde(default)]
+    pub data: Bytes,
+    /// False if the transaction reverted and moved nothing
+    pub success: bool,
+}
+
+impl HistoryEntry {
+    /// The wallet's balance after this entry, given the balance before it
+    pub fn balance_after(&self, balance: U256) -> U256 {
+        if !self.success {
+            return balance;
+        }
+        match self.kind {
+            HistoryKind::Deposit => balance + self.amount,
+            _ => balance.saturating_sub(self.amount + self.paymaster_fee),
+        }
+    }
+}
+
+/// A scan in progress: the entries found so far and where to pick up
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryCheckpoint {
+    pub version: u32,
+    pub chain_id: u64,
+    pub wallet: Address,
+    /// The first block not scanned yet
+    pub next_block: u64,
+    pub entries: Vec<HistoryEntry>,
+}
+
+impl HistoryCheckpoint {
+    pub fn new(chain_id: u64, wallet: Address, from_block: u64) -> Self {
+        HistoryCheckpoint {
+            version: HISTORY_CHECKPOINT_VERSION,
+            chain_id,
+            wallet,
+            next_block: from_block,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<HistoryCheckpoint> {
+        let contents = std::fs::read_to_string(path).map_err(|e| file_error(path, e))?;
+        let checkpoint: HistoryCheckpoint =
+            serde_json::from_str(&contents).map_err(|e| file_error(path, e))?;
+        if checkpoint.version != HISTORY_CHECKPOINT_VERSION {
+            return Err(file_error(
+                path,
+                format!("unsupported version {}", checkpoint.version),
+            ));
+        }
+        Ok(checkpoint)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let contents = serde_json::to_string_pretty(self).map_err(|e| file_error(path, e))?;
+        std::fs::write(path, contents + "\n").map_err(|e| file_error(path, e))
+    }
+}
+
+fn file_error(path: &Path, err: impl std::fmt::Display) -> Error {
+    Error::InvalidInput {
+        what: "history checkpoint file",
+        reason: format!("{}: {err}", path.display()),
+    }
+}
+
+/// A transaction to the wallet, decoded from its calldata alone
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DecodedCall {
+    kind: HistoryKind,
+    to: Address,
+    /// None when it depends on the balance: `withdrawAll` and recoveries
+    amount: Option<U256>,
+    paymaster_fee: U256,
+    data: Bytes,
+}
+
+/// Classifies a transaction sent to `wallet`; None if it can't move value
+fn decode_call(wallet: Address, value: U256, input: &[u8]) -> Option<DecodedCall> {
+    if input.is_empty() {
+        return (!value.is_zero()).then(|| DecodedCall {
+            kind: HistoryKind::Deposit,
+            to: wallet,
+            amount: Some(value),
+            paymaster_fee: U256::ZERO,
+            data: Bytes::new(),
+        });
+    }
+    let selector = input.get(..4)?;
+    if selector == GpgRewardWallet::withdrawAllCall::SELECTOR {
+        let call = GpgRewardWallet::withdrawAllCall::abi_decode(input).ok()?;
+        Some(DecodedCall {
+            kind: HistoryKind::WithdrawAll,
+            to: call.to,
+            amount: None,
+            paymaster_fee: call.paymasterFee,
+            data: Bytes::new(),
+        })
+    } else if selector == GpgRewardWallet::executeWithSigCall::SELECTOR {
+        let call = GpgRewardWallet::executeWithSigCall::abi_decode(input).ok()?;
+        Some(DecodedCall {
+            kind: HistoryKind::Execute,
+            to: call.to,
+            amount: Some(call.value),
+            paymaster_fee: call.paymasterFee,
+            data: call.data,
+        })
+    } else if selector == GpgRewardWallet::recoverPostDeadmanSwitchCall::SELECTOR {
+        Some(DecodedCall {
+            kind: HistoryKind::Recovery,
+            to: Address::ZERO,
+            amount: None,
+            paymaster_fee: U256::ZERO,
+            data: Bytes::new(),
+        })
+    } else {
+        None
+    }
+}
+
+impl<P: Provider> GpgWalletClient<P> {
+    /// The first block where `wallet` has code or a balance, found by
+    /// bisection; None if it has neither yet. Needs an archive node.
+    ///
+    /// Funds can only leave through the deployed wallet, so once either holds
+    /// it holds for every later block.
+    pub async fn first_active_block(&self, wallet: Address) -> Result<Option<u64>> {
+        let latest = self.provider().get_block_number().await?;
+        if !self.is_active_at(wallet, latest).await? {
+            return Ok(None);
+        }
+        let (mut low, mut high) = (0, latest);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.is_active_at(wallet, mid).await? {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        Ok(Some(low))
+    }
+
+    async fn is_active_at(&self, wallet: Address, block: u64) -> Result<bool> {
+        let block = BlockId::number(block);
+        let balance = self.provider().get_balance(wallet).block_id(block).await?;
+        if !balance.is_zero() {
+            return Ok(true);
+        }
+        let code = self.provider().get_code_at(wallet).block_id(block).await?;
+        Ok(!code.is_empty())
+    }
+
+    /// Scans `from_block..=to_block` for transactions to `wallet` that move
+    /// value: deposits, signed withdrawals and calls, and recoveries
+    ///
+    /// Only top-level transactions are seen; value sent by another contract
+    /// needs a tracing node. A `withdrawAll` or recovery's amount is worked
+    /// out from the balance before its block, which needs an archive node.
+    ///
+    /// Every block is fetched in full, [`HISTORY_CONCURRENCY`] at a time, so a
+    /// long range is still slow; scan it in chunks to save progress.
+    pub async fn history(
+        &self,
+        wallet: Address,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<HistoryEntry>> {
+        let blocks: Vec<Vec<HistoryEntry>> = stream::iter(from_block..=to_block)
+            .map(|number| async move {
+                let block = self.full_block(number).await?;
+                self.block_entries(wallet, &block).await
+            })
+            .buffered(HISTORY_CONCURRENCY)
+            .try_collect()
+            .await?;
+        Ok(blocks.into_iter().flatten().collect())
+    }
+
+    pub(crate) async fn full_block(&self, number: u64) -> Result<Block> {
+        self.provider()
+            .get_block_by_number(number.into())
+            .full()
+            .await?
+            .ok_or_else(|| Error::InvalidInput {
+                what: "block range",
+                reason: format!("block {number} not found"),
+            })
+    }
+
+    /// The entries for `wallet` among one block's transactions
+    pub(crate) async fn block_entries(
+        &self,
+        wallet: Address,
+        block: &Block,
+    ) -> Result<Vec<HistoryEntry>> {
+        let number = block.header.number;
+        let mut entries = Vec::new();
+        // Balance and nonce as of the previous block, fetched at the first
+        // entry and then kept up to date through this one's entries
+        let mut balance = None;
+        let mut nonce = None;
+        for tx in block.transactions.txns() {
+            if tx.to() != Some(wallet) {
+                continue;
+            }
+            let Some(call) = decode_call(wallet, tx.value(), tx.input()) else {
+                continue;
+            };
+            let success = self
+                .provider()
+                .get_transaction_receipt(tx.tx_hash())
+                .await?
+                .is_some_and(|receipt| receipt.status());
+            let before = number.saturating_sub(1);
+            let signed = matches!(call.kind, HistoryKind::WithdrawAll | HistoryKind::Execute);
+            if balance.is_none() {
+                balance = Some(
+                    self.provider()
+                        .get_balance(wallet)
+                        .block_id(BlockId::number(before))
+                        .await?,
+                );
+            }
+            if signed && nonce.is_none() {
+                let before = BlockId::number(before);
+                let is_deployed = !self
+                    .provider()
+                    .get_code_at(wallet)
+                    .block_id(before)
+                    .await?
+                    .is_empty();
+                // Deployed in this same block, so no nonce has been used yet
+                nonce = Some(if is_deployed {
+                    self.wallet(wallet).nextNonce().block(before).call().await?
+                } else {
+                    U256::ZERO
+                });
+            }
+            let (to, amount) = match call.kind {
+                HistoryKind::WithdrawAll => (
+                    call.to,
+                    balance
+                        .unwrap_or_default()
+                        .saturating_sub(call.paymaster_fee),
+                ),
+                HistoryKind::Recovery => (
+                    self.wallet(wallet)
+                        .admin()
+                        .block(BlockId::number(number))
+                        .call()
+                        .await?,
+                    balance.unwrap_or_default(),
+                ),
+                _ => (call.to, call.amount.unwrap_or_default()),
+            };
+            let entry = HistoryEntry {
+                block: number,
+                timestamp: block.header.timestamp,
+                tx_hash: tx.tx_hash(),
+                kind: call.kind,
+                from: tx.from(),
+                to,
+                amount,
+                paymaster_fee: call.paymaster_fee,
+                nonce: signed.then(|| nonce.unwrap_or_default()),
+                data: call.data,
+                success,
+            };
+            balance = balance.map(|balance| entry.balance_after(balance));
+            if signed && success {
+                nonce = nonce.map(|nonce| nonce + U256::from(1));
+            }
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::Network;
+    use alloy::{
+        consensus::{self, Signed, TxEnvelope, TxLegacy},
+        primitives::{B256, Signature, TxKind, address},
+        providers::{ProviderBuilder, mock::Asserter},
+        rpc::types::{BlockTransactions, Header, Transaction},
+        sol_types::SolValue,
+    };
+    use serde_json::json;
+
+    const WALLET: Address = address!("0xd7baae85d719c2e8e27a70194471ef4b6b253d33");
+    const TO: Address = address!("0x590b78eaF98053eFBa4107Eed2e0F70D2B90A45d");
+
+    #[test]
+    fn test_decode_call() {
+        let deposit = decode_call(WALLET, U256::from(5), &[]).unwrap();
+        assert_eq!(deposit.kind, HistoryKind::Deposit);
+        assert_eq!(deposit.to, WALLET);
+        assert_eq!(deposit.amount, Some(U256::from(5)));
+        assert!(decode_call(WALLET, U256::ZERO, &[]).is_none());
+
+        let withdraw = GpgRewardWallet::withdrawAllCall {
+            to: TO,
+            paymasterFee: U256::from(1000),
+            deadline: U256::from(1_700_000_000),
+            pubKey: Bytes::from_static(b"key"),
+            signature: Bytes::from_static(b"sig"),
+        }
+        .abi_encode();
+        let withdraw = decode_call(WALLET, U256::ZERO, &withdraw).unwrap();
+        assert_eq!(withdraw.kind, HistoryKind::WithdrawAll);
+        assert_eq!(withdraw.to, TO);
+        assert_eq!(withdraw.amount, None);
+        assert_eq!(withdraw.paymaster_fee, U256::from(1000));
+
+        let execute = GpgRewardWallet::executeWithSigCall {
+            to: TO,
+            value: U256::from(42),
+            data: Bytes::from_static(&[0xde, 0xad]),
+            paymasterFee: U256::from(7),
+            deadline: U256::from(1_700_000_000),
+            pubKey: Bytes::new(),
+            signature: Bytes::new(),
+        }
+        .abi_encode();
+        let execute = decode_call(WALLET, U256::ZERO, &execute).unwrap();
+        assert_eq!(execute.kind, HistoryKind::Execute);
+        assert_eq!(execute.amount, Some(U256::from(42)));
+        assert_eq!(execute.data, Bytes::from_static(&[0xde, 0xad]));
+
+        let recovery = GpgRewardWallet::recoverPostDeadmanSwitchCall {}.abi_encode();
+        assert_eq!(
+            decode_call(WALLET, U256::ZERO, &recovery).unwrap().kind,
+            HistoryKind::Recovery
+        );
+        assert!(decode_call(WALLET, U256::ZERO, &[0x12, 0x34, 0x56, 0x78]).is_none());
+        assert!(decode_call(WALLET, U256::ZERO, &[0x12]).is_none());
+    }
+
+    fn transaction(hash: u8, value: U256, input: Vec<u8>) -> Transaction {
+        let tx = TxLegacy {
+            to: TxKind::Call(WALLET),
+            value,
+            input: input.into(),
+            ..Default::default()
+        };
+        let signed =
+            Signed::new_unchecked(tx, Signature::test_signature(), B256::repeat_byte(hash));
+        Transaction {
+            inner: consensus::transaction::Recovered::new_unchecked(TxEnvelope::Legacy(signed), TO),
+            block_hash: None,
+            block_number: Some(20),
+            transaction_index: None,
+            effective_gas_price: None,
+            block_timestamp: None,
+        }
+    }
+
+    fn receipt(hash: u8) -> serde_json::Value {
+        json!({
+            "transactionHash": B256::repeat_byte(hash),
+            "transactionIndex": "0x0",
+            "blockHash": B256::repeat_byte(0x11),
+            "blockNumber": "0x14",
+            "from": TO,
+            "to": WALLET,
+            "cumulativeGasUsed": "0x5208",
+            "gasUsed": "0x5208",
+            "effectiveGasPrice": "0x1",
+            "contractAddress": null,
+            "logs": [],
+            "logsBloom": Bytes::from(vec![0u8; 256]),
+            "type": "0x0",
+            "status": "0x1",
+        })
+    }
+
+    #[tokio::test]
+    async fn test_block_entries_deposit_then_withdraw_all() -> Result<()> {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        let client = GpgWalletClient::new(provider, Network::tea_sepolia());
+        let withdraw = GpgRewardWallet::withdrawAllCall {
+            to: TO,
+            paymasterFee: U256::from(10),
+            deadline: U256::from(1_700_000_000),
+            pubKey: Bytes::new(),
+            signature: Bytes::new(),
+        }
+        .abi_encode();
+        let block = Block {
+            header: Header {
+                inner: consensus::Header {
+                    number: 20,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            transactions: BlockTransactions::Full(vec![
+                transaction(1, U256::from(100), Vec::new()),
+                transaction(2, U256::ZERO, withdraw),
+            ]),
+            ..Default::default()
+        };
+
+        // 50 wei before the block, then a deposit of 100 and everything out
+        asserter.push_success(&receipt(1));
+        asserter.push_success(&U256::from(50));
+        asserter.push_success(&receipt(2));
+        asserter.push_success(&Bytes::from_static(&[0x60, 0x01]));
+        asserter.push_success(&Bytes::from(U256::from(3).abi_encode()));
+
+        let entries = client.block_entries(WALLET, &block).await?;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].kind, HistoryKind::Deposit);
+        assert_eq!(entries[0].amount, U256::from(100));
+        assert_eq!(entries[1].kind, HistoryKind::WithdrawAll);
+        assert_eq!(entries[1].amount, U256::from(140));
+        assert_eq!(entries[1].nonce, Some(U256::from(3)));
+        assert!(entries.iter().all(|entry| entry.success));
+
+        // An RPC failure isn't taken for an undeployed wallet
+        asserter.push_success(&receipt(2));
+        asserter.push_success(&U256::from(50));
+        asserter.push_failure_msg("header not found");
+        let block = Block {
+            transactions: BlockTransactions::Full(vec![transaction(
+                2,
+                U256::ZERO,
+                GpgRewardWallet::withdrawAllCall {
+                    to: TO,
+                    paymasterFee: U256::ZERO,
+                    deadline: U256::ZERO,
+                    pubKey: Bytes::new(),
+                    signature: Bytes::new(),
+                }
+                .abi_encode(),
+            )]),
+            ..block
+        };
+        assert!(client.block_entries(WALLET, &block).await.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_history_checkpoint_file() -> Result<()> {
+        let mut checkpoint = HistoryCheckpoint::new(10218, WALLET, 100);
+        checkpoint.next_block = 200;
+        checkpoint.entries.push(HistoryEntry {
+            block: 150,
+            timestamp: 1_700_000_000,
+            tx_hash: TxHash::repeat_byte(0xab),
+            kind: HistoryKind::Execute,
+            from: TO,
+            to: TO,
+            amount: U256::from(42),
+            paymaster_fee: U256::from(7),
+            nonce: Some(U256::from(3)),
+            data: Bytes::from_static(&[0xde, 0xad]),
+            success: true,
+        });
+        let path =
+            std::env::temp_dir().join(format!("history-checkpoint-{}.json", std::process::id()));
+        checkpoint.save(&path)?;
+        let loaded = HistoryCheckpoint::load(&path);
+        std::fs::remove_file(&path).ok();
+        assert_eq!(loaded?, checkpoint);
+        Ok(())
+    }
+}
@@ -0,0 +1,187 @@
+use alloy::{
+    primitives::{Address, FixedBytes, U256, hex},
+    providers::Provider,
+    sol_types::Eip712Domain,
+};
+
+use crate::{
+    Result,
+    client::GpgWalletClient,
+    deadman::DeadmanSwitch,
+    eip712::{execute_typehash, withdraw_all_typehash},
+    network::Network,
+    utils::key_id_to_bytes,
+};
+
+/// Everything a deployed wallet exposes, plus what it should match
+#[derive(Debug, Clone)]
+pub struct WalletInfo {
+    /// The key ID the wallet was looked up by
+    pub requested_key_id: FixedBytes<8>,
+    pub address: Address,
+    pub balance: U256,
+    pub key_id: FixedBytes<8>,
+    pub deadman_switch: DeadmanSwitch,
+    pub implementation: Address,
+    /// What the deployer currently clones new wallets from
+    pub deployer_implementation: Address,
+    pub next_nonce: U256,
+    pub gpg_verifier: Address,
+    pub withdraw_all_typehash: FixedBytes<32>,
+    pub execute_typehash: FixedBytes<32>,
+    pub domain: Eip712Domain,
+}
+
+impl WalletInfo {
+    /// Describes each way the wallet disagrees with the deployer or `network`
+    pub fn mismatches(&self, network: &Network) -> Vec<String> {
+        let mut mismatches = Vec::new();
+        if self.key_id != self.requested_key_id {
+            mismatches.push(format!(
+                "wallet key ID is {}, not the requested {}",
+                hex::encode_upper(self.key_id),
+                hex::encode_upper(self.requested_key_id)
+            ));
+        }
+        if self.implementation != self.deployer_implementation {
+            mismatches.push(format!(
+                "wallet implementation is {}, but the deployer uses {}",
+                self.implementation, self.deployer_implementation
+            ));
+        }
+        // A different typehash means signatures made by this tool won't verify
+        let typehashes = [
+            (
+                "WITHDRAW_ALL_TYPEHASH",
+                self.withdraw_all_typehash,
+                withdraw_all_typehash(),
+            ),
+            (
+                "EXECUTE_TYPEHASH",
+                self.execute_typehash,
+                execute_typehash(),
+            ),
+        ];
+        for (name, actual, expected) in typehashes {
+            if actual != expected {
+                mismatches.push(format!("wallet {name} is {actual}, expected {expected}"));
+            }
+        }
+        if let Some(verifying_contract) = self.domain.verifying_contract
+            && verifying_contract != self.address
+        {
+            mismatches.push(format!(
+                "EIP-712 domain verifying contract is {verifying_contract}, not the wallet"
+            ));
+        }
+        if let Some(chain_id) = self.domain.chain_id
+            && chain_id != U256::from(network.chain_id)
+        {
+            mismatches.push(format!(
+                "EIP-712 domain chain ID is {chain_id}, but network {} is chain {}",
+                network.name, network.chain_id
+            ));
+        }
+        mismatches
+    }
+}
+
+impl<P: Provider> GpgWalletClient<P> {
+    pub async fn inspect(&self, key_id: &str) -> Result<WalletInfo> {
+        let requested_key_id = key_id_to_bytes(key_id)?;
+        let deadman_switch = self.deadman_switch(key_id).await?;
+        let address = deadman_switch.wallet;
+        let wallet = self.wallet(address);
+        Ok(WalletInfo {
+            requested_key_id,
+            address,
+            balance: self.provider().get_balance(address).await?,
+            key_id: wallet.keyId().call().await?,
+            deadman_switch,
+            implementation: wallet.implementation().call().await?,
+            deployer_implementation: self.deployer().implementation().call().await?,
+            next_nonce: wallet.nextNonce().call().await?,
+            gpg_verifier: wallet.GPG_VERIFIER().call().await?,
+            withdraw_all_typehash: wallet.WITHDRAW_ALL_TYPEHASH().call().await?,
+            execute_typehash: wallet.EXECUTE_TYPEHASH().call().await?,
+            domain: self.eip712_domain(address).await?,
+        })
+    }
+
+    /// The wallet's EIP-712 domain, with the fields it leaves out unset
+    pub async fn eip712_domain(&self, wallet: Address) -> Result<Eip712Domain> {
+        let domain = self.wallet(wallet).eip712Domain().call().await?;
+        // ERC-5267: bits 0-4 flag name, version, chainId, verifyingContract, salt
+        let has = |bit: u8| domain.fields[0] & (1 << bit) != 0;
+        Ok(Eip712Domain::new(
+            has(0).then(|| domain.name.into()),
+            has(1).then(|| domain.version.into()),
+            has(2).then_some(domain.chainId),
+            has(3).then_some(domain.verifyingContract),
+            has(4).then_some(domain.salt),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{
+        primitives::{B256, Bytes, address},
+        providers::{ProviderBuilder, mock::Asserter},
+        sol_types::SolValue,
+    };
+
+    const KEY_ID: &str = "95469C7E3DFC90B1";
+    const WALLET: Address = address!("0xd7baae85d719c2e8e27a70194471ef4b6b253d33");
+    const IMPLEMENTATION: Address = address!("0x590b78eaF98053eFBa4107Eed2e0F70D2B90A45d");
+
+    fn push(asserter: &Asserter, value: impl SolValue) {
+        asserter.push_success(&Bytes::from(value.abi_encode()));
+    }
+
+    #[tokio::test]
+    async fn test_inspect() -> Result<()> {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        let network = Network::tea_sepolia();
+        let client = GpgWalletClient::new(provider, network.clone());
+        asserter.push_success(&Bytes::from((WALLET, true).abi_encode_params()));
+        push(&asserter, Address::ZERO);
+        push(&asserter, U256::from(1_000));
+        asserter.push_success(&U256::from(42));
+        push(&asserter, FixedBytes::<8>::repeat_byte(0x11));
+        push(&asserter, IMPLEMENTATION);
+        push(&asserter, Address::repeat_byte(0x22));
+        push(&asserter, U256::from(7));
+        push(&asserter, Address::repeat_byte(0x33));
+        push(&asserter, withdraw_all_typehash());
+        push(&asserter, B256::repeat_byte(0x55));
+        asserter.push_success(&Bytes::from(
+            (
+                FixedBytes::<1>::from([0x0f]),
+                "GpgRewardWallet".to_string(),
+                "1".to_string(),
+                U256::from(network.chain_id),
+                WALLET,
+                B256::ZERO,
+                Vec::<U256>::new(),
+            )
+                .abi_encode_params(),
+        ));
+
+        let info = client.inspect(KEY_ID).await?;
+        assert_eq!(info.balance, U256::from(42));
+        assert_eq!(info.deadman_switch.trigger_date, 1_000);
+        assert_eq!(info.next_nonce, U256::from(7));
+        assert_eq!(info.execute_typehash, B256::repeat_byte(0x55));
+        assert_eq!(info.domain.name.as_deref(), Some("GpgRewardWallet"));
+        assert_eq!(info.domain.salt, None);
+        let mismatches = info.mismatches(&network);
+        assert_eq!(mismatches.len(), 3);
+        assert!(mismatches[0].contains("1111111111111111"));
+        assert!(mismatches[1].contains("implementation"));
+        assert!(mismatches[2].contains("EXECUTE_TYPEHASH"));
+        Ok(())
+    }
+}
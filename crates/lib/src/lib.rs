@@ -1,3 +1,25 @@
+pub mod batch;
+pub mod client;
+pub mod config;
+pub mod deadman;
 pub mod deployer;
+pub mod eip712;
+pub mod error;
+pub mod exec;
+pub mod history;
+pub mod inspect;
+pub mod network;
+#[cfg(feature = "openpgp")]
+pub mod openpgp;
+pub mod relay;
+pub mod request;
+pub mod simulate;
+pub mod token;
+pub mod tx;
 pub mod utils;
+#[cfg(feature = "verify")]
+pub mod verify;
 pub mod wallet;
+pub mod watch;
+
+pub use error::{Error, Result};
@@ -0,0 +1,253 @@
+use crate::{Error, Result, batch::MULTICALL3_ADDRESS};
+use alloy::primitives::{Address, FixedBytes, address};
+use reqwest::Url as URL;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+pub const DEFAULT_NETWORK: &str = "tea-sepolia";
+pub const DEFAULT_RPC_URL: &str = env!("RPC_URL");
+pub const DEFAULT_DEPLOYER_ADDRESS: &str = env!("GPG_DEPLOYER_ADDRESS");
+
+pub const TEA_SEPOLIA_CHAIN_ID: u64 = 10218;
+pub const TEA_SEPOLIA_EXPLORER_URL: &str = "https://sepolia.tea.xyz";
+
+pub const LOCAL_NETWORK: &str = "local";
+pub const LOCAL_CHAIN_ID: u64 = 31337;
+pub const LOCAL_RPC_URL: &str = "http://127.0.0.1:8545";
+pub const LOCAL_WS_URL: &str = "ws://127.0.0.1:8545";
+/// Where the first contract deployed by anvil's default account lands
+pub const LOCAL_DEPLOYER_ADDRESS: Address = address!("0x5FbDB2315678afecb367f032d93F642f64180aa3");
+
+/// A named chain the GPG wallet deployer lives on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Network {
+    pub name: String,
+    pub chain_id: u64,
+    /// The first URL is used; the rest are alternates for the same chain
+    pub rpc_urls: Vec<URL>,
+    pub deployer: Address,
+    pub native_symbol: String,
+    pub explorer_url: Option<URL>,
+    /// The deployer's `implementation()`, if known; lets wallet addresses be
+    /// predicted without an RPC endpoint
+    pub implementation: Option<Address>,
+    /// Multicall3, for batched reads
+    pub multicall3: Address,
+    /// A WebSocket endpoint for the same chain, for block subscriptions
+    pub ws_url: Option<URL>,
+}
+
+impl Network {
+    pub fn tea_sepolia() -> Network {
+        Network {
+            name: DEFAULT_NETWORK.to_string(),
+            chain_id: TEA_SEPOLIA_CHAIN_ID,
+            rpc_urls: vec![DEFAULT_RPC_URL.parse().expect("Invalid built-in RPC URL")],
+            deployer: Address::parse_checksummed(DEFAULT_DEPLOYER_ADDRESS, None)
+                .expect("Invalid built-in deployer address"),
+            native_symbol: "TEA".to_string(),
+            explorer_url: Some(
+                TEA_SEPOLIA_EXPLORER_URL
+                    .parse()
+                    .expect("Invalid built-in explorer URL"),
+            ),
+            implementation: None,
+            multicall3: MULTICALL3_ADDRESS,
+            ws_url: None,
+        }
+    }
+
+    /// An anvil or hardhat devnet on its default port, with the deployer
+    /// deployed first from the default account
+    pub fn local() -> Network {
+        Network {
+            name: LOCAL_NETWORK.to_string(),
+            chain_id: LOCAL_CHAIN_ID,
+            rpc_urls: vec![LOCAL_RPC_URL.parse().expect("Invalid built-in RPC URL")],
+            deployer: LOCAL_DEPLOYER_ADDRESS,
+            native_symbol: "ETH".to_string(),
+            explorer_url: None,
+            implementation: None,
+            multicall3: MULTICALL3_ADDRESS,
+            ws_url: Some(
+                LOCAL_WS_URL
+                    .parse()
+                    .expect("Invalid built-in WebSocket URL"),
+            ),
+        }
+    }
+
+    #[inline]
+    pub fn rpc_url(&self) -> &URL {
+        &self.rpc_urls[0]
+    }
+
+    /// Block explorer link for a transaction, if the network has an explorer
+    pub fn tx_url(&self, tx_hash: FixedBytes<32>) -> Option<URL> {
+        self.explorer_url
+            .as_ref()
+            .and_then(|url| url.join(&format!("tx/{tx_hash}")).ok())
+    }
+
+    /// Block explorer link for an address, if the network has an explorer
+    pub fn address_url(&self, address: Address) -> Option<URL> {
+        self.explorer_url
+            .as_ref()
+            .and_then(|url| url.join(&format!("address/{address}")).ok())
+    }
+}
+
+/// The networks known without any configuration
+pub fn builtin_networks() -> BTreeMap<String, Network> {
+    [Network::tea_sepolia(), Network::local()]
+        .into_iter()
+        .map(|network| (network.name.clone(), network))
+        .collect()
+}
+
+// A `[networks.<name>]` table in the config file, e.g.
+//   [networks.local]
+//   chain_id = 31337
+//   rpc_urls = ["http://127.0.0.1:8545"]
+//   deployer = "0x..."
+//   native_symbol = "ETH"
+//   implementation = "0x..."  # optional, for offline address prediction
+//   multicall3 = "0x..."  # optional, if not at the usual address
+//   ws_url = "ws://127.0.0.1:8546"  # optional, for watch
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct NetworkEntry {
+    chain_id: u64,
+    #[serde(alias = "rpc_url")]
+    rpc_urls: OneOrMany,
+    deployer: String,
+    native_symbol: Option<String>,
+    explorer_url: Option<String>,
+    implementation: Option<String>,
+    multicall3: Option<String>,
+    ws_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl NetworkEntry {
+    pub(crate) fn into_network(self, name: &str) -> Result<Network> {
+        let rpc_urls = match self.rpc_urls {
+            OneOrMany::One(url) => vec![url],
+            OneOrMany::Many(urls) => urls,
+        };
+        if rpc_urls.is_empty() {
+            return Err(Error::Config(format!("Network {name} has no RPC URLs")));
+        }
+        Ok(Network {
+            name: name.to_string(),
+            chain_id: self.chain_id,
+            rpc_urls: rpc_urls
+                .iter()
+                .map(|url| url.parse())
+                .collect::<Result<_, _>>()
+                .map_err(|e| Error::Config(format!("Invalid RPC URL for network {name}: {e}")))?,
+            deployer: self.deployer.parse().map_err(|e| {
+                Error::Config(format!("Invalid deployer address for network {name}: {e}"))
+            })?,
+            native_symbol: self.native_symbol.unwrap_or_else(|| "ETH".to_string()),
+            explorer_url: self
+                .explorer_url
+                .map(|url| url.parse())
+                .transpose()
+                .map_err(|e| {
+                    Error::Config(format!("Invalid explorer URL for network {name}: {e}"))
+                })?,
+            implementation: self
+                .implementation
+                .map(|address| address.parse())
+                .transpose()
+                .map_err(|e| {
+                    Error::Config(format!(
+                        "Invalid implementation address for network {name}: {e}"
+                    ))
+                })?,
+            multicall3: self
+                .multicall3
+                .map(|address| address.parse())
+                .transpose()
+                .map_err(|e| {
+                    Error::Config(format!(
+                        "Invalid Multicall3 address for network {name}: {e}"
+                    ))
+                })?
+                .unwrap_or(MULTICALL3_ADDRESS),
+            ws_url: self
+                .ws_url
+                .map(|url| url.parse())
+                .transpose()
+                .map_err(|e| {
+                    Error::Config(format!("Invalid WebSocket URL for network {name}: {e}"))
+                })?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{address, b256};
+
+    #[test]
+    fn test_tea_sepolia() {
+        let network = Network::tea_sepolia();
+        assert_eq!(network.chain_id, TEA_SEPOLIA_CHAIN_ID);
+        assert_eq!(network.rpc_url().as_str(), DEFAULT_RPC_URL);
+        assert_eq!(
+            network.deployer,
+            address!("0x1e67d22542bd2eAFff45BEA53BebDA73E7A231dd")
+        );
+        assert_eq!(
+            network
+                .tx_url(b256!(
+                    "0x8941bd5962cdb275a3f5f1ffa623aa3be1fc40f55b0b308ab906cf9f7ef39cac"
+                ))
+                .map(|url| url.to_string()),
+            Some("https://sepolia.tea.xyz/tx/0x8941bd5962cdb275a3f5f1ffa623aa3be1fc40f55b0b308ab906cf9f7ef39cac".to_string())
+        );
+    }
+
+    #[test]
+    fn test_builtin_networks() {
+        let networks = builtin_networks();
+        assert_eq!(
+            networks.keys().map(String::as_str).collect::<Vec<_>>(),
+            [LOCAL_NETWORK, DEFAULT_NETWORK]
+        );
+        let local = &networks[LOCAL_NETWORK];
+        assert_eq!(local.chain_id, LOCAL_CHAIN_ID);
+        assert_eq!(local.rpc_url().as_str(), "http://127.0.0.1:8545/");
+        assert_eq!(local.tx_url(FixedBytes::ZERO), None);
+    }
+
+    #[test]
+    fn test_network_entry() -> Result<(), Box<dyn std::error::Error>> {
+        let entry: NetworkEntry = toml::from_str(
+            r#"
+            chain_id = 31337
+            rpc_url = "http://127.0.0.1:8545"
+            deployer = "0x5FbDB2315678afecb367f032d93F642f64180aa3"
+            "#,
+        )?;
+        let network = entry.into_network("local")?;
+        assert_eq!(network.name, "local");
+        assert_eq!(network.chain_id, 31337);
+        assert_eq!(network.rpc_url().as_str(), "http://127.0.0.1:8545/");
+        assert_eq!(network.native_symbol, "ETH");
+        assert_eq!(network.explorer_url, None);
+        assert_eq!(network.implementation, None);
+        assert_eq!(network.multicall3, MULTICALL3_ADDRESS);
+        assert_eq!(network.ws_url, None);
+        Ok(())
+    }
+}
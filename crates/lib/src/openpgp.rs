@@ -0,0 +1,123 @@
+use alloy::primitives::Bytes;
+use pgp::{
+    composed::{Deserializable, DetachedSignature, SignedSecretKey},
+    crypto::hash::HashAlgorithm,
+    packet::PacketTrait,
+    ser::Serialize,
+    types::{KeyDetails, Password},
+};
+use std::path::Path;
+
+use crate::{Error, Result, wallet::SigningData};
+
+/// A detached signature and the public key to verify it with, as the
+/// `GpgRewardWallet` verifier takes them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgpSignature {
+    pub signature: Bytes,
+    pub public_key: Bytes,
+}
+
+/// An OpenPGP secret key, signing in-process instead of through `gpg`
+#[derive(Debug)]
+pub struct SecretKey {
+    key: SignedSecretKey,
+    passphrase: Password,
+}
+
+impl SecretKey {
+    /// Loads an armored or binary secret key file
+    pub fn from_file(path: &Path, passphrase: Option<&str>) -> Result<SecretKey> {
+        let contents =
+            std::fs::read(path).map_err(|e| Error::OpenPgp(format!("{}: {e}", path.display())))?;
+        SecretKey::from_bytes(&contents, passphrase)
+    }
+
+    pub fn from_bytes(contents: &[u8], passphrase: Option<&str>) -> Result<SecretKey> {
+        let (key, _) = SignedSecretKey::from_reader_single(contents)
+            .map_err(|e| Error::OpenPgp(format!("Invalid secret key: {e}")))?;
+        Ok(SecretKey {
+            key,
+            passphrase: passphrase
+                .map(Password::from)
+                .unwrap_or_else(Password::empty),
+        })
+    }
+
+    /// The primary key's 16 hex character ID, as the deployer expects it
+    pub fn key_id(&self) -> String {
+        self.key.legacy_key_id().to_string().to_uppercase()
+    }
+
+    /// Just the primary public key packet, without user IDs or subkeys
+    pub fn public_key(&self) -> Result<Bytes> {
+        let mut public_key = Vec::new();
+        self.key
+            .primary_key
+            .public_key()
+            .to_writer_with_header(&mut public_key)
+            .map_err(|e| Error::OpenPgp(format!("Failed to encode public key: {e}")))?;
+        Ok(public_key.into())
+    }
+
+    /// A binary detached signature over `data`, made with the primary key
+    pub fn sign(&self, data: &[u8]) -> Result<Bytes> {
+        let signature = DetachedSignature::sign_binary_data(
+            rand::thread_rng(),
+            &*self.key,
+            &self.passphrase,
+            HashAlgorithm::Sha256,
+            data,
+        )
+        .map_err(|e| Error::OpenPgp(format!("Failed to sign: {e}")))?;
+        signature
+            .to_bytes()
+            .map(Bytes::from)
+            .map_err(|e| Error::OpenPgp(format!("Failed to encode signature: {e}")))
+    }
+
+    pub fn sign_blob(&self, signing_data: &SigningData) -> Result<PgpSignature> {
+        Ok(PgpSignature {
+            signature: self.sign(signing_data.blob.as_slice())?,
+            public_key: self.public_key()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify::tests::generate;
+    use pgp::composed::SignedPublicKey;
+
+    #[test]
+    fn test_sign() -> Result<()> {
+        let contents = generate("hunter2");
+        let key = SecretKey::from_bytes(&contents, Some("hunter2"))?;
+        let (signed, _) =
+            SignedSecretKey::from_reader_single(contents.as_slice()).expect("Failed to parse key");
+        assert_eq!(key.key_id().len(), 16);
+        assert_eq!(
+            key.key_id(),
+            signed.legacy_key_id().to_string().to_uppercase()
+        );
+
+        let blob = [0xab; 32];
+        let signature = key.sign(&blob)?;
+        let (signature, _) = DetachedSignature::from_reader_single(signature.as_ref())
+            .expect("Failed to parse signature");
+        let public_key = SignedPublicKey::from(signed);
+        signature
+            .verify(&public_key.primary_key, &blob)
+            .expect("Signature doesn't verify");
+        assert!(signature.verify(&public_key.primary_key, &[0; 32]).is_err());
+
+        assert!(
+            SecretKey::from_bytes(&contents, Some("wrong"))?
+                .sign(&blob)
+                .is_err()
+        );
+        assert!(SecretKey::from_bytes(b"not a key", None).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,492 @@
+use alloy::{
+    primitives::{Address, Bytes, FixedBytes, TxHash, U256},
+    providers::Provider,
+    rpc::types::{TransactionInput, TransactionReceipt, TransactionRequest},
+    sol_types::SolCall,
+};
+use reqwest::Url as URL;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::time::{Duration, Instant};
+
+use crate::{
+    Error, Result,
+    client::GpgWalletClient,
+    utils::{parse_bytes, unix_now},
+    wallet::{GpgRewardWallet, Operation, SigningData},
+};
+
+/// A signed `withdrawAll` or `executeWithSig` call for a relayer to submit
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelayRequest {
+    pub key_id: String,
+    pub operation: Operation,
+    pub to: Address,
+    #[serde(default)]
+    pub value: U256,
+    #[serde(default)]
+    pub data: Bytes,
+    pub paymaster_fee: U256,
+    pub deadline: U256,
+    pub public_key: String,
+    pub signature: String,
+}
+
+impl RelayRequest {
+    pub fn new(
+        key_id: &str,
+        signing_data: &SigningData,
+        public_key: &str,
+        signature: &str,
+    ) -> RelayRequest {
+        RelayRequest {
+            key_id: key_id.to_string(),
+            operation: signing_data.operation,
+            to: signing_data.to,
+            value: signing_data.value,
+            data: signing_data.data.clone(),
+            paymaster_fee: signing_data.paymaster_fee,
+            deadline: signing_data.deadline,
+            public_key: public_key.to_string(),
+            signature: signature.to_string(),
+        }
+    }
+}
+
+/// What a relayer reports back after submitting a request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelaySubmission {
+    pub tx_hash: TxHash,
+    /// The struct hash the signature was checked against
+    pub struct_hash: FixedBytes<32>,
+    pub gas_estimate: u64,
+}
+
+/// Where a submitted transaction stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TxStatus {
+    Pending,
+    Success { block_number: Option<u64> },
+    Reverted { block_number: Option<u64> },
+}
+
+impl<P: Provider> GpgWalletClient<P> {
+    /// Builds the wallet transaction for `request`, checks the wallet's
+    /// struct hash locally and simulates it, so a stale nonce or expired
+    /// deadline fails before anything is sent; with the `verify` feature, so
+    /// does a bad signature
+    ///
+    /// Returns the transaction and the on-chain struct hash it was checked against.
+    pub async fn check_relay(
+        &self,
+        request: &RelayRequest,
+    ) -> Result<(TransactionRequest, FixedBytes<32>)> {
+        if request.deadline < U256::from(unix_now()) {
+            return Err(Error::DeadlineExpired(format!(
+                "deadline {} is in the past",
+                request.deadline
+            )));
+        }
+        let address = self.deployed_address(&request.key_id).await?;
+        let wallet = self.wallet(address);
+        let nonce = wallet.nextNonce().call().await?;
+        let pubkey = parse_bytes("public key", &request.public_key)?;
+        let signature = parse_bytes("signature", &request.signature)?;
+        let signing_data = |blob| SigningData {
+            blob,
+            wallet: address,
+            deadline: request.deadline,
+            operation: request.operation,
+            to: request.to,
+            value: request.value,
+            data: request.data.clone(),
+            paymaster_fee: request.paymaster_fee,
+            nonce,
+        };
+        let (struct_hash, input) = match request.operation {
+            Operation::WithdrawAll => {
+                if !request.value.is_zero() || !request.data.is_empty() {
+                    return Err(Error::InvalidInput {
+                        what: "relay request",
+                        reason: "withdrawAll takes no value or data".to_string(),
+                    });
+                }
+                let struct_hash = wallet
+                    .getWithdrawAllStructHash(
+                        request.to,
+                        request.paymaster_fee,
+                        request.deadline,
+                        nonce,
+                    )
+                    .call()
+                    .await?;
+                let input = GpgRewardWallet::withdrawAllCall {
+                    to: request.to,
+                    paymasterFee: request.paymaster_fee,
+                    deadline: request.deadline,
+                    pubKey: pubkey.clone(),
+                    signature: signature.clone(),
+                }
+                .abi_encode();
+                (struct_hash, input)
+            }
+            Operation::Execute => {
+                let struct_hash = wallet
+                    .getExecuteStructHash(
+                        request.to,
+                        request.value,
+                        request.data.clone(),
+                        request.paymaster_fee,
+                        request.deadline,
+                        nonce,
+                    )
+                    .call()
+                    .await?;
+                let input = GpgRewardWallet::executeWithSigCall {
+                    to: request.to,
+                    value: request.value,
+                    data: request.data.clone(),
+                    paymasterFee: request.paymaster_fee,
+                    deadline: request.deadline,
+                    pubKey: pubkey.clone(),
+                    signature: signature.clone(),
+                }
+                .abi_encode();
+                (struct_hash, input)
+            }
+        };
+        self.check_reported_hash(&signing_data(struct_hash))?;
+        #[cfg(feature = "verify")]
+        self.verify_signature(address, struct_hash, &pubkey, &signature)
+            .await?;
+        let tx = TransactionRequest::default()
+            .to(address)
+            .input(TransactionInput::new(input.into()));
+        self.provider().call(tx.clone()).await?;
+        Ok((tx, struct_hash))
+    }
+
+    /// Checks `request`, makes sure its paymaster fee covers the estimated
+    /// gas, then sends it without waiting for it to be mined
+    pub async fn submit_relay(&self, request: &RelayRequest) -> Result<RelaySubmission> {
+        let (tx, struct_hash) = self.check_relay(request).await?;
+        let gas_estimate = self.provider().estimate_gas(tx.clone()).await?;
+        let gas_price = self.provider().get_gas_price().await?;
+        let required = U256::from(gas_estimate) * U256::from(gas_price);
+        if request.paymaster_fee < required {
+            return Err(Error::PaymasterFeeTooLow {
+                offered: request.paymaster_fee,
+                required,
+            });
+        }
+        let pending = self
+            .provider()
+            .send_transaction(tx.gas_limit(gas_estimate))
+            .await?;
+        Ok(RelaySubmission {
+            tx_hash: *pending.tx_hash(),
+            struct_hash,
+            gas_estimate,
+        })
+    }
+
+    pub async fn transaction_status(&self, tx_hash: TxHash) -> Result<TxStatus> {
+        Ok(
+            match self.provider().get_transaction_receipt(tx_hash).await? {
+                None => TxStatus::Pending,
+                Some(receipt) => mined_status(&receipt),
+            },
+        )
+    }
+
+    /// The status of a mined transaction sent from this client's sender, for
+    /// hashes nothing else vouches for; None if it isn't mined, or was sent
+    /// by someone else
+    pub async fn sent_transaction_status(&self, tx_hash: TxHash) -> Result<Option<TxStatus>> {
+        let Some(sender) = self.sender() else {
+            return Ok(None);
+        };
+        Ok(self
+            .provider()
+            .get_transaction_receipt(tx_hash)
+            .await?
+            .filter(|receipt| receipt.from == sender)
+            .map(|receipt| mined_status(&receipt)))
+    }
+}
+
+fn mined_status(receipt: &TransactionReceipt) -> TxStatus {
+    if receipt.status() {
+        TxStatus::Success {
+            block_number: receipt.block_number,
+        }
+    } else {
+        TxStatus::Reverted {
+            block_number: receipt.block_number,
+        }
+    }
+}
+
+/// Talks to a `tea-gpg-wallet-relayer` over HTTP, so signed operations can be
+/// submitted without a local funded key
+#[derive(Debug, Clone)]
+pub struct RelayerClient {
+    url: URL,
+    http: reqwest::Client,
+}
+
+impl RelayerClient {
+    pub fn new(url: &str) -> Result<RelayerClient> {
+        Ok(RelayerClient {
+            url: base_url(url)?,
+            http: reqwest::Client::new(),
+        })
+    }
+
+    pub fn url(&self) -> &URL {
+        &self.url
+    }
+
+    pub async fn submit(&self, request: &RelayRequest) -> Result<RelaySubmission> {
+        let response = self
+            .http
+            .post(self.endpoint("relay")?)
+            .json(request)
+            .send()
+            .await?;
+        parse_response(response).await
+    }
+
+    pub async fn status(&self, tx_hash: TxHash) -> Result<TxStatus> {
+        let response = self
+            .http
+            .get(self.endpoint(&format!("status/{tx_hash}"))?)
+            .send()
+            .await?;
+        parse_response(response).await
+    }
+
+    /// Polls the relayer every `interval` until the transaction is mined
+    pub async fn wait_until_mined(
+        &self,
+        tx_hash: TxHash,
+        interval: Duration,
+        timeout: Duration,
+    ) -> Result<TxStatus> {
+        let started = Instant::now();
+        loop {
+            match self.status(tx_hash).await? {
+                TxStatus::Pending if started.elapsed() < timeout => {
+                    tokio::time::sleep(interval).await
+                }
+                TxStatus::Pending => {
+                    return Err(Error::Relayer(format!(
+                        "transaction {tx_hash} still pending after {}s",
+                        timeout.as_secs()
+                    )));
+                }
+                status => return Ok(status),
+            }
+        }
+    }
+
+    fn endpoint(&self, path: &str) -> Result<URL> {
+        self.url.join(path).map_err(|e| Error::InvalidInput {
+            what: "relayer URL",
+            reason: e.to_string(),
+        })
+    }
+}
+
+// Endpoints are joined onto the base URL, which only keeps its last path
+// segment when it ends with a slash
+fn base_url(url: &str) -> Result<URL> {
+    let mut base: URL = url.parse().map_err(|e| Error::InvalidInput {
+        what: "relayer URL",
+        reason: format!("{url:?}: {e}"),
+    })?;
+    if !base.path().ends_with('/') {
+        base.set_path(&format!("{}/", base.path()));
+    }
+    Ok(base)
+}
+
+// The relayer answers errors with `{"error": "..."}`
+async fn parse_response<T: DeserializeOwned>(response: reqwest::Response) -> Result<T> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response.json().await?);
+    }
+    let body = response.text().await?;
+    let message = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|json| json["error"].as_str().map(str::to_string))
+        .unwrap_or(body);
+    Err(Error::Relayer(format!("{status}: {message}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        eip712::{struct_hash, wallet_domain},
+        network::Network,
+    };
+    use alloy::{
+        primitives::address,
+        providers::{ProviderBuilder, mock::Asserter},
+        sol_types::SolValue,
+    };
+
+    fn request(operation: Operation) -> RelayRequest {
+        RelayRequest {
+            key_id: "95469C7E3DFC90B1".to_string(),
+            operation,
+            to: address!("0x590b78eaF98053eFBa4107Eed2e0F70D2B90A45d"),
+            value: U256::ZERO,
+            data: Bytes::new(),
+            paymaster_fee: U256::from(1000),
+            deadline: U256::from(unix_now() + 60),
+            public_key: "0xabcd".to_string(),
+            signature: "0x1234".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_relay_request_json() -> std::result::Result<(), serde_json::Error> {
+        let request = request(Operation::WithdrawAll);
+        let json = serde_json::to_value(&request)?;
+        assert_eq!(json["operation"], "withdrawAll");
+        assert_eq!(serde_json::from_value::<RelayRequest>(json)?, request);
+        assert_eq!(
+            serde_json::to_value(TxStatus::Success {
+                block_number: Some(5)
+            })?,
+            serde_json::json!({ "status": "success", "block_number": 5 })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_base_url() -> Result<()> {
+        assert_eq!(
+            base_url("https://relay.example.com/api")?
+                .join("relay")
+                .ok(),
+            "https://relay.example.com/api/relay".parse().ok()
+        );
+        assert_eq!(
+            base_url("http://127.0.0.1:8080")?.as_str(),
+            "http://127.0.0.1:8080/"
+        );
+        assert!(base_url("not a url").is_err());
+        Ok(())
+    }
+
+    const WALLET: Address = address!("0xd7baae85d719c2e8e27a70194471ef4b6b253d33");
+
+    fn mocked_client() -> (GpgWalletClient<impl Provider>, Asserter) {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        (
+            GpgWalletClient::new(provider, Network::tea_sepolia()),
+            asserter,
+        )
+    }
+
+    // The struct hash the wallet should report for `request`, at nonce 7
+    fn expected_hash(request: &RelayRequest) -> FixedBytes<32> {
+        struct_hash(
+            &wallet_domain(Network::tea_sepolia().chain_id, WALLET),
+            &SigningData {
+                blob: FixedBytes::ZERO,
+                wallet: WALLET,
+                deadline: request.deadline,
+                operation: request.operation,
+                to: request.to,
+                value: request.value,
+                data: request.data.clone(),
+                paymaster_fee: request.paymaster_fee,
+                nonce: U256::from(7),
+            },
+        )
+    }
+
+    fn push_struct_hash(asserter: &Asserter, hash: FixedBytes<32>) {
+        asserter.push_success(&Bytes::from((WALLET, true).abi_encode_params()));
+        asserter.push_success(&Bytes::from(U256::from(7).abi_encode()));
+        asserter.push_success(&Bytes::from(hash.abi_encode()));
+    }
+
+    #[cfg(feature = "verify")]
+    #[tokio::test]
+    async fn test_submit_relay_checks_fee() -> Result<()> {
+        use crate::verify::tests::{generate, key_id, parse, public_key_packet, sign};
+        use alloy::primitives::hex;
+
+        let (client, asserter) = mocked_client();
+        let key = parse(&generate(""));
+        let unsigned = request(Operation::WithdrawAll);
+        let hash = expected_hash(&unsigned);
+        let signed = RelayRequest {
+            public_key: hex::encode_prefixed(public_key_packet(&key)),
+            signature: hex::encode_prefixed(sign(&key, hash.as_slice())),
+            ..unsigned
+        };
+        let push_signed = || {
+            push_struct_hash(&asserter, hash);
+            asserter.push_success(&Bytes::from(key_id(&key).abi_encode()));
+        };
+        push_signed();
+        asserter.push_success(&Bytes::new());
+        asserter.push_success(&U256::from(100));
+        asserter.push_success(&U256::from(20));
+        assert!(matches!(
+            client.submit_relay(&signed).await,
+            Err(Error::PaymasterFeeTooLow { required, .. }) if required == U256::from(2000)
+        ));
+
+        // Signed over a different struct hash: rejected before it's simulated
+        let missigned = RelayRequest {
+            signature: hex::encode_prefixed(sign(&key, FixedBytes::<32>::ZERO.as_slice())),
+            ..signed
+        };
+        push_signed();
+        assert!(matches!(
+            client.submit_relay(&missigned).await,
+            Err(Error::SignatureRejected(_))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_check_relay_rejects() -> Result<()> {
+        let (client, asserter) = mocked_client();
+        let request = request(Operation::Execute);
+        push_struct_hash(&asserter, expected_hash(&request));
+        // The right struct hash gets past the check, to whatever call comes next
+        asserter.push_failure_msg("execution reverted");
+        assert!(client.check_relay(&request).await.is_err());
+        assert!(asserter.read_q().is_empty());
+
+        // An RPC endpoint reporting some other struct hash: nothing more is
+        // asked of it, so nothing is simulated or sent
+        push_struct_hash(&asserter, FixedBytes::ZERO);
+        assert!(matches!(
+            client.submit_relay(&request).await,
+            Err(Error::StructHashMismatch { .. })
+        ));
+        assert!(asserter.read_q().is_empty());
+
+        let mut bad = request.clone();
+        bad.operation = Operation::WithdrawAll;
+        bad.value = U256::from(1);
+        asserter.push_success(&Bytes::from((WALLET, true).abi_encode_params()));
+        asserter.push_success(&Bytes::from(U256::from(7).abi_encode()));
+        assert!(matches!(
+            client.check_relay(&bad).await,
+            Err(Error::InvalidInput { .. })
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,395 @@
+use alloy::{
+    primitives::{Address, Bytes, FixedBytes, TxHash, U256},
+    providers::Provider,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::path::Path;
+
+use crate::{
+    Error, Result,
+    client::GpgWalletClient,
+    eip712::{self, wallet_domain},
+    relay::RelayRequest,
+    wallet::{Operation, SigningData},
+};
+
+/// Bumped when the file layout changes incompatibly
+pub const SIGNING_REQUEST_VERSION: u32 = 1;
+
+/// A self-describing signing request, written by an online machine, signed on
+/// an offline one and carried back to be submitted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SigningRequest {
+    pub version: u32,
+    pub network: String,
+    pub chain_id: u64,
+    pub wallet: Address,
+    pub key_id: String,
+    pub operation: Operation,
+    pub to: Address,
+    #[serde(default)]
+    pub value: U256,
+    #[serde(default)]
+    pub data: Bytes,
+    pub paymaster_fee: U256,
+    pub nonce: U256,
+    pub deadline: U256,
+    /// What actually gets signed
+    pub struct_hash: FixedBytes<32>,
+    /// The same message as EIP-712 typed data, for review before signing
+    pub typed_data: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+impl SigningRequest {
+    pub fn load(path: &Path) -> Result<SigningRequest> {
+        let contents = std::fs::read_to_string(path).map_err(|e| file_error(path, e))?;
+        let request: SigningRequest =
+            serde_json::from_str(&contents).map_err(|e| file_error(path, e))?;
+        if request.version != SIGNING_REQUEST_VERSION {
+            return Err(file_error(
+                path,
+                format!("unsupported version {}", request.version),
+            ));
+        }
+        Ok(request)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let contents = serde_json::to_string_pretty(self).map_err(|e| file_error(path, e))?;
+        std::fs::write(path, contents + "\n").map_err(|e| file_error(path, e))
+    }
+
+    pub fn signing_data(&self) -> SigningData {
+        SigningData {
+            blob: self.struct_hash,
+            wallet: self.wallet,
+            deadline: self.deadline,
+            operation: self.operation,
+            to: self.to,
+            value: self.value,
+            data: self.data.clone(),
+            paymaster_fee: self.paymaster_fee,
+            nonce: self.nonce,
+        }
+    }
+
+    /// Fails unless `struct_hash` is what the request's fields hash to, so a
+    /// tampered file can't get a different message signed than the one shown
+    pub fn check_struct_hash(&self) -> Result<()> {
+        eip712::check_struct_hash(
+            &wallet_domain(self.chain_id, self.wallet),
+            &self.signing_data(),
+        )
+    }
+
+    /// The public key and signature, once the request has been signed
+    pub fn signed(&self) -> Result<(&str, &str)> {
+        match (&self.public_key, &self.signature) {
+            (Some(public_key), Some(signature)) => Ok((public_key, signature)),
+            _ => Err(Error::InvalidInput {
+                what: "signing request",
+                reason: "request has not been signed".to_string(),
+            }),
+        }
+    }
+
+    pub fn relay_request(&self) -> Result<RelayRequest> {
+        let (public_key, signature) = self.signed()?;
+        Ok(RelayRequest::new(
+            &self.key_id,
+            &self.signing_data(),
+            public_key,
+            signature,
+        ))
+    }
+}
+
+fn file_error(path: &Path, err: impl std::fmt::Display) -> Error {
+    Error::InvalidInput {
+        what: "signing request file",
+        reason: format!("{}: {err}", path.display()),
+    }
+}
+
+/// Field names follow the wallet's `get*StructHash` parameters
+fn typed_data(
+    domain_name: &str,
+    domain_version: &str,
+    chain_id: U256,
+    wallet: Address,
+    signing_data: &SigningData,
+) -> Value {
+    let (primary_type, fields, message) = match signing_data.operation {
+        Operation::WithdrawAll => (
+            "WithdrawAll",
+            json!([
+                { "name": "to", "type": "address" },
+                { "name": "paymasterFee", "type": "uint256" },
+                { "name": "deadline", "type": "uint256" },
+                { "name": "nonce", "type": "uint256" },
+            ]),
+            json!({
+                "to": signing_data.to,
+                "paymasterFee": signing_data.paymaster_fee.to_string(),
+                "deadline": signing_data.deadline.to_string(),
+                "nonce": signing_data.nonce.to_string(),
+            }),
+        ),
+        Operation::Execute => (
+            "Execute",
+            json!([
+                { "name": "to", "type": "address" },
+                { "name": "value", "type": "uint256" },
+                { "name": "data", "type": "bytes" },
+                { "name": "paymasterFee", "type": "uint256" },
+                { "name": "deadline", "type": "uint256" },
+                { "name": "nonce", "type": "uint256" },
+            ]),
+            json!({
+                "to": signing_data.to,
+                "value": signing_data.value.to_string(),
+                "data": signing_data.data,
+                "paymasterFee": signing_data.paymaster_fee.to_string(),
+                "deadline": signing_data.deadline.to_string(),
+                "nonce": signing_data.nonce.to_string(),
+            }),
+        ),
+    };
+    json!({
+        "types": {
+            "EIP712Domain": [
+                { "name": "name", "type": "string" },
+                { "name": "version", "type": "string" },
+                { "name": "chainId", "type": "uint256" },
+                { "name": "verifyingContract", "type": "address" },
+            ],
+            primary_type: fields,
+        },
+        "primaryType": primary_type,
+        "domain": {
+            "name": domain_name,
+            "version": domain_version,
+            "chainId": chain_id.saturating_to::<u64>(),
+            "verifyingContract": wallet,
+        },
+        "message": message,
+    })
+}
+
+impl<P: Provider> GpgWalletClient<P> {
+    /// Wraps signing data from one of the `get_*_signable_hash` calls into a
+    /// request that can be signed offline
+    pub async fn prepare_signing_request(
+        &self,
+        key_id: &str,
+        signing_data: &SigningData,
+    ) -> Result<SigningRequest> {
+        let wallet = signing_data.wallet;
+        let domain = self.wallet(wallet).eip712Domain().call().await?;
+        Ok(SigningRequest {
+            version: SIGNING_REQUEST_VERSION,
+            network: self.network().name.clone(),
+            chain_id: self.network().chain_id,
+            wallet,
+            key_id: key_id.to_string(),
+            operation: signing_data.operation,
+            to: signing_data.to,
+            value: signing_data.value,
+            data: signing_data.data.clone(),
+            paymaster_fee: signing_data.paymaster_fee,
+            nonce: signing_data.nonce,
+            deadline: signing_data.deadline,
+            struct_hash: signing_data.blob,
+            typed_data: typed_data(
+                &domain.name,
+                &domain.version,
+                domain.chainId,
+                wallet,
+                signing_data,
+            ),
+            public_key: None,
+            signature: None,
+        })
+    }
+
+    /// Sends a signed request from this client's signer
+    pub async fn submit_signing_request(&self, request: &SigningRequest) -> Result<TxHash> {
+        let (public_key, signature) = request.signed()?;
+        if request.chain_id != self.network().chain_id {
+            return Err(Error::InvalidInput {
+                what: "signing request",
+                reason: format!(
+                    "prepared for chain {}, but network {} is chain {}",
+                    request.chain_id,
+                    self.network().name,
+                    self.network().chain_id
+                ),
+            });
+        }
+        let wallet = self.deployed_address(&request.key_id).await?;
+        if wallet != request.wallet {
+            return Err(Error::InvalidInput {
+                what: "signing request",
+                reason: format!(
+                    "prepared for wallet {}, but key ID {} maps to {wallet}",
+                    request.wallet, request.key_id
+                ),
+            });
+        }
+        match request.operation {
+            Operation::WithdrawAll => {
+                self.sweep_gpg_key(
+                    &request.key_id,
+                    &request.to.to_string(),
+                    request.paymaster_fee,
+                    request.deadline,
+                    public_key,
+                    signature,
+                )
+                .await
+            }
+            Operation::Execute => self
+                .execute_with_sig(
+                    &request.key_id,
+                    &request.signing_data(),
+                    public_key,
+                    signature,
+                )
+                .await
+                .map(|execution| execution.tx_hash),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::Network;
+    use alloy::{
+        primitives::{B256, address},
+        providers::{ProviderBuilder, mock::Asserter},
+        sol_types::SolValue,
+    };
+
+    const WALLET: Address = address!("0xd7baae85d719c2e8e27a70194471ef4b6b253d33");
+
+    fn signing_data() -> SigningData {
+        SigningData {
+            blob: B256::repeat_byte(0xab),
+            wallet: WALLET,
+            deadline: U256::from(1_700_000_000),
+            operation: Operation::WithdrawAll,
+            to: address!("0x590b78eaF98053eFBa4107Eed2e0F70D2B90A45d"),
+            value: U256::ZERO,
+            data: Bytes::new(),
+            paymaster_fee: U256::from(1000),
+            nonce: U256::from(7),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prepare_signing_request() -> Result<()> {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        let client = GpgWalletClient::new(provider, Network::tea_sepolia());
+        asserter.push_success(&Bytes::from(
+            (
+                FixedBytes::<1>::from([0x0f]),
+                "GpgRewardWallet".to_string(),
+                "1".to_string(),
+                U256::from(10218),
+                WALLET,
+                B256::ZERO,
+                Vec::<U256>::new(),
+            )
+                .abi_encode_params(),
+        ));
+        let request = client
+            .prepare_signing_request("95469C7E3DFC90B1", &signing_data())
+            .await?;
+        assert_eq!(request.wallet, WALLET);
+        assert_eq!(request.struct_hash, B256::repeat_byte(0xab));
+        assert_eq!(request.typed_data["primaryType"], "WithdrawAll");
+        assert_eq!(request.typed_data["domain"]["chainId"], 10218);
+        assert_eq!(request.typed_data["message"]["paymasterFee"], "1000");
+        assert_eq!(request.signing_data().blob, request.struct_hash);
+        assert!(request.relay_request().is_err());
+        Ok(())
+    }
+
+    fn request() -> SigningRequest {
+        SigningRequest {
+            version: SIGNING_REQUEST_VERSION,
+            network: "tea-sepolia".to_string(),
+            chain_id: 10218,
+            wallet: WALLET,
+            key_id: "95469C7E3DFC90B1".to_string(),
+            operation: Operation::WithdrawAll,
+            to: signing_data().to,
+            value: U256::ZERO,
+            data: Bytes::new(),
+            paymaster_fee: U256::from(1000),
+            nonce: U256::from(7),
+            deadline: U256::from(1_700_000_000),
+            struct_hash: B256::repeat_byte(0xab),
+            typed_data: typed_data(
+                "GpgRewardWallet",
+                "1",
+                U256::from(10218),
+                WALLET,
+                &signing_data(),
+            ),
+            public_key: None,
+            signature: None,
+        }
+    }
+
+    #[test]
+    fn test_signing_request_file() -> Result<()> {
+        let mut request = request();
+        request.public_key = Some("abcd".to_string());
+        request.signature = Some("1234".to_string());
+        let path =
+            std::env::temp_dir().join(format!("signing-request-{}.json", std::process::id()));
+        request.save(&path)?;
+        let loaded = SigningRequest::load(&path);
+        std::fs::remove_file(&path).ok();
+        let loaded = loaded?;
+        assert_eq!(loaded, request);
+        assert_eq!(loaded.relay_request()?.signature, "1234");
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_struct_hash() -> Result<(), Box<dyn std::error::Error>> {
+        let mut request = request();
+        request.struct_hash =
+            eip712::struct_hash(&wallet_domain(10218, WALLET), &request.signing_data());
+        request.check_struct_hash()?;
+
+        // A hash that doesn't match the fields shown, straight from a file
+        let mut tampered = serde_json::to_value(&request)?;
+        tampered["struct_hash"] = json!(B256::repeat_byte(0xab));
+        let path = std::env::temp_dir().join(format!(
+            "tampered-signing-request-{}.json",
+            std::process::id()
+        ));
+        std::fs::write(&path, tampered.to_string())?;
+        let loaded = SigningRequest::load(&path);
+        std::fs::remove_file(&path).ok();
+        assert!(matches!(
+            loaded?.check_struct_hash(),
+            Err(Error::StructHashMismatch { .. })
+        ));
+
+        // Fields changed under the original hash
+        request.to = Address::repeat_byte(0xee);
+        assert!(request.check_struct_hash().is_err());
+        Ok(())
+    }
+}
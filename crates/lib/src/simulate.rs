@@ -0,0 +1,307 @@
+use alloy::{
+    primitives::{Address, Bytes, U256},
+    providers::Provider,
+    rpc::types::{
+        TransactionRequest,
+        state::{StateOverride, StateOverridesBuilder},
+    },
+    sol_types::SolCall,
+};
+
+use crate::{
+    Result,
+    client::GpgWalletClient,
+    deployer::wallet_runtime_code,
+    token::{IERC20, TokenInfo},
+    tx::FeeEstimate,
+    utils::key_id_to_bytes,
+};
+
+/// A transaction a mutating operation would send, checked with `eth_call`
+/// and `eth_estimateGas` but never broadcast
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulatedTx {
+    /// Exactly what would be sent, with the client's [`TxOptions`](crate::tx::TxOptions) applied
+    pub request: TransactionRequest,
+    pub fee: FeeEstimate,
+    /// What `eth_call` returned
+    pub output: Bytes,
+}
+
+impl SimulatedTx {
+    pub fn value(&self) -> U256 {
+        self.request.value.unwrap_or_default()
+    }
+}
+
+/// What a mutating operation would do, and what it would cost
+#[derive(Debug, Clone, PartialEq)]
+pub struct Simulation {
+    pub wallet: Address,
+    /// Whether the wallet was deployed beforehand
+    pub was_deployed: bool,
+    /// In the order they'd be sent; empty when there's nothing to do
+    pub transactions: Vec<SimulatedTx>,
+    /// The wallet's balance of the asset involved, now and afterwards
+    pub balance: U256,
+    pub new_balance: U256,
+}
+
+impl Simulation {
+    /// The most the transactions can cost in gas
+    pub fn total_fee(&self) -> U256 {
+        self.transactions.iter().map(|tx| tx.fee.total()).sum()
+    }
+
+    /// The most the sender can spend: native value sent plus gas
+    pub fn total_cost(&self) -> U256 {
+        self.transactions
+            .iter()
+            .map(|tx| tx.value() + tx.fee.total())
+            .sum()
+    }
+}
+
+impl<P: Provider> GpgWalletClient<P> {
+    /// [`ensure_deployed`](GpgWalletClient::ensure_deployed) without sending anything
+    pub async fn simulate_ensure_deployed(&self, key_id: &str) -> Result<Simulation> {
+        let prediction = self.predict_address(key_id).await?;
+        let balance = self
+            .provider()
+            .get_balance(prediction.walletAddress)
+            .await?;
+        let mut transactions = Vec::new();
+        if !prediction.isDeployed {
+            transactions.push(self.simulate_deploy(key_id).await?);
+        }
+        self.assign_nonces(&mut transactions).await?;
+        Ok(Simulation {
+            wallet: prediction.walletAddress,
+            was_deployed: prediction.isDeployed,
+            transactions,
+            balance,
+            new_balance: balance,
+        })
+    }
+
+    /// [`send_to_gpg_key`](GpgWalletClient::send_to_gpg_key) without sending anything
+    ///
+    /// A transfer to a wallet that isn't deployed yet is simulated against
+    /// the wallet's code, though not its initialized storage.
+    pub async fn simulate_send_to_gpg_key(&self, key_id: &str, amount: U256) -> Result<Simulation> {
+        let prediction = self.predict_address(key_id).await?;
+        let balance = self
+            .provider()
+            .get_balance(prediction.walletAddress)
+            .await?;
+        let mut transactions = Vec::new();
+        if !prediction.isDeployed {
+            transactions.push(self.simulate_deploy(key_id).await?);
+        }
+        let transfer = TransactionRequest::default()
+            .to(prediction.walletAddress)
+            .value(amount);
+        let overrides = if prediction.isDeployed {
+            None
+        } else {
+            Some(
+                StateOverridesBuilder::default()
+                    .with_code(
+                        prediction.walletAddress,
+                        wallet_runtime_code(self.implementation().await?),
+                    )
+                    .build(),
+            )
+        };
+        transactions.push(self.simulate_transaction(transfer, overrides).await?);
+        self.assign_nonces(&mut transactions).await?;
+        Ok(Simulation {
+            wallet: prediction.walletAddress,
+            was_deployed: prediction.isDeployed,
+            transactions,
+            balance,
+            new_balance: balance + amount,
+        })
+    }
+
+    /// [`send_token_to_gpg_key`](GpgWalletClient::send_token_to_gpg_key)
+    /// without sending anything; balances are in the token's units
+    pub async fn simulate_send_token_to_gpg_key(
+        &self,
+        key_id: &str,
+        token: &TokenInfo,
+        amount: U256,
+    ) -> Result<Simulation> {
+        let prediction = self.predict_address(key_id).await?;
+        let balance = self
+            .token(token.address)
+            .balanceOf(prediction.walletAddress)
+            .call()
+            .await?;
+        let mut transactions = Vec::new();
+        if !prediction.isDeployed {
+            transactions.push(self.simulate_deploy(key_id).await?);
+        }
+        let transfer = TransactionRequest::default().to(token.address).input(
+            IERC20::transferCall::new((prediction.walletAddress, amount))
+                .abi_encode()
+                .into(),
+        );
+        transactions.push(self.simulate_transaction(transfer, None).await?);
+        self.assign_nonces(&mut transactions).await?;
+        Ok(Simulation {
+            wallet: prediction.walletAddress,
+            was_deployed: prediction.isDeployed,
+            transactions,
+            balance,
+            new_balance: balance + amount,
+        })
+    }
+
+    /// [`sweep_gpg_key`](GpgWalletClient::sweep_gpg_key) without sending
+    /// anything, after the same signature checks
+    pub async fn simulate_sweep_gpg_key(
+        &self,
+        key_id: &str,
+        to: &str,
+        paymaster_fee: U256,
+        deadline: U256,
+        public_key: &str,
+        signature: &str,
+    ) -> Result<Simulation> {
+        let (wallet, tx) = self
+            .withdraw_all_request(key_id, to, paymaster_fee, deadline, public_key, signature)
+            .await?;
+        let balance = self.provider().get_balance(wallet).await?;
+        let mut transactions = vec![self.simulate_transaction(tx, None).await?];
+        self.assign_nonces(&mut transactions).await?;
+        Ok(Simulation {
+            wallet,
+            was_deployed: true,
+            transactions,
+            balance,
+            new_balance: U256::ZERO,
+        })
+    }
+
+    async fn simulate_deploy(&self, key_id: &str) -> Result<SimulatedTx> {
+        let deploy = self
+            .deployer()
+            .deploy_call(key_id_to_bytes(key_id)?)
+            .into_transaction_request();
+        self.simulate_transaction(deploy, None).await
+    }
+
+    /// Calls and estimates `tx`, applying the client's options
+    async fn simulate_transaction(
+        &self,
+        mut tx: TransactionRequest,
+        overrides: Option<StateOverride>,
+    ) -> Result<SimulatedTx> {
+        if tx.from.is_none() {
+            tx.from = self.sender();
+        }
+        let output = self
+            .provider()
+            .call(tx.clone())
+            .overrides_opt(overrides.clone())
+            .await?;
+        if tx.gas.is_none() && self.tx_options().gas_limit.is_none() {
+            tx.gas = Some(
+                self.provider()
+                    .estimate_gas(tx.clone())
+                    .overrides_opt(overrides)
+                    .await?,
+            );
+        }
+        let request = self.prepare_transaction(tx).await?;
+        let fee = self.estimate_fee(&request).await?;
+        Ok(SimulatedTx {
+            request,
+            fee,
+            output,
+        })
+    }
+
+    /// Numbers the transactions from `--nonce`, or else from the sender's
+    /// pending transaction count
+    async fn assign_nonces(&self, transactions: &mut [SimulatedTx]) -> Result<()> {
+        let first = match (self.tx_options().nonce, self.sender()) {
+            (Some(nonce), _) => nonce,
+            (None, Some(sender)) if !transactions.is_empty() => {
+                self.provider()
+                    .get_transaction_count(sender)
+                    .pending()
+                    .await?
+            }
+            _ => return Ok(()),
+        };
+        for (nonce, tx) in (first..).zip(transactions.iter_mut()) {
+            tx.request.nonce = Some(nonce);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{network::Network, tx::TxOptions};
+    use alloy::{
+        primitives::{U64, address},
+        providers::{ProviderBuilder, mock::Asserter},
+        sol_types::SolValue,
+    };
+
+    const KEY_ID: &str = "95469C7E3DFC90B1";
+    const WALLET: Address = address!("0xd7baae85d719c2e8e27a70194471ef4b6b253d33");
+    const GWEI: u128 = 1_000_000_000;
+
+    #[tokio::test]
+    async fn test_simulate_send_to_gpg_key() -> Result<()> {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        let options = TxOptions {
+            max_fee_per_gas: Some(2 * GWEI),
+            max_priority_fee_per_gas: Some(GWEI),
+            nonce: Some(7),
+            ..Default::default()
+        };
+        let client = GpgWalletClient::new(provider, Network::tea_sepolia())
+            .with_tx_options(options)
+            .with_sender(Address::repeat_byte(0xaa));
+
+        // Not deployed, holding 5 wei: a deploy, then the transfer
+        asserter.push_success(&Bytes::from((WALLET, false).abi_encode_params()));
+        asserter.push_success(&U256::from(5));
+        asserter.push_success(&Bytes::from(WALLET.abi_encode()));
+        asserter.push_success(&U64::from(150_000));
+        asserter.push_success(&Bytes::from(Address::repeat_byte(1).abi_encode()));
+        asserter.push_success(&Bytes::new());
+        asserter.push_success(&U64::from(30_000));
+
+        let simulation = client
+            .simulate_send_to_gpg_key(KEY_ID, U256::from(100))
+            .await?;
+        assert_eq!(simulation.wallet, WALLET);
+        assert!(!simulation.was_deployed);
+        assert_eq!(simulation.new_balance, U256::from(105));
+        let [deploy, transfer] = simulation.transactions.as_slice() else {
+            panic!("expected a deploy and a transfer");
+        };
+        assert_eq!(deploy.request.nonce, Some(7));
+        assert_eq!(deploy.fee.gas_limit, 150_000);
+        assert_eq!(transfer.request.nonce, Some(8));
+        assert_eq!(transfer.value(), U256::from(100));
+        assert_eq!(transfer.fee.max_priority_fee_per_gas, Some(GWEI));
+        assert_eq!(
+            simulation.total_fee(),
+            U256::from(180_000) * U256::from(2 * GWEI)
+        );
+        assert_eq!(
+            simulation.total_cost(),
+            simulation.total_fee() + U256::from(100)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,176 @@
+use alloy::{
+    primitives::{Address, U256},
+    providers::Provider,
+    sol,
+    sol_types::SolCall,
+};
+
+use crate::{
+    Error, Result,
+    client::{GpgWalletClient, Transfer},
+    utils::{parse_address, units_to_decimal},
+    wallet::{Execution, SigningData},
+};
+
+sol!(
+    #[sol(rpc)]
+    interface IERC20 {
+        function balanceOf(address owner) external view returns (uint256);
+        function transfer(address to, uint256 amount) external returns (bool);
+        function decimals() external view returns (uint8);
+        function symbol() external view returns (string);
+    }
+);
+
+/// The ERC-20 metadata needed to parse and display amounts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenInfo {
+    pub address: Address,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+impl TokenInfo {
+    /// Formats an amount in the token's smallest unit, e.g. "1.5 USDC"
+    pub fn format(&self, amount: U256) -> String {
+        format!(
+            "{} {}",
+            units_to_decimal(amount, self.decimals as usize),
+            self.symbol
+        )
+    }
+}
+
+impl<P: Provider> GpgWalletClient<P> {
+    pub fn token(&self, address: Address) -> IERC20::IERC20Instance<&P> {
+        IERC20::new(address, self.provider())
+    }
+
+    pub async fn token_info(&self, token: &str) -> Result<TokenInfo> {
+        let address = parse_address(token)?;
+        let contract = self.token(address);
+        Ok(TokenInfo {
+            address,
+            symbol: contract.symbol().call().await?,
+            decimals: contract.decimals().call().await?,
+        })
+    }
+
+    pub async fn get_key_id_token_balance(&self, key_id: &str, token: &TokenInfo) -> Result<U256> {
+        let wallet = self.deployed_address(key_id).await?;
+        Ok(self.token(token.address).balanceOf(wallet).call().await?)
+    }
+
+    /// Transfers `amount` of the token from the signer to the key ID's wallet,
+    /// deploying it first if needed
+    pub async fn send_token_to_gpg_key(
+        &self,
+        key_id: &str,
+        token: &TokenInfo,
+        amount: U256,
+    ) -> Result<Transfer> {
+        let destination = self.ensure_deployed(key_id).await?;
+        let contract = self.token(token.address);
+
+        let receipt = self
+            .send_transaction(
+                contract
+                    .transfer(destination.walletAddress, amount)
+                    .into_transaction_request(),
+            )
+            .await?;
+
+        Ok(Transfer {
+            tx_hash: receipt.transaction_hash,
+            wallet: destination.walletAddress,
+            new_balance: contract.balanceOf(destination.walletAddress).call().await?,
+        })
+    }
+
+    /// Struct hash for an `executeWithSig` call of `token.transfer(to, amount)`
+    pub async fn get_token_withdraw_signable_hash(
+        &self,
+        key_id: &str,
+        token: &TokenInfo,
+        to: &str,
+        amount: U256,
+        paymaster_fee: U256,
+    ) -> Result<SigningData> {
+        let balance = self.get_key_id_token_balance(key_id, token).await?;
+        if balance < amount {
+            return Err(Error::InsufficientFunds(format!(
+                "wallet holds {}, {} requested",
+                token.format(balance),
+                token.format(amount)
+            )));
+        }
+        let data = IERC20::transferCall {
+            to: parse_address(to)?,
+            amount,
+        }
+        .abi_encode();
+        self.get_execute_signable_hash(
+            key_id,
+            &token.address.to_string(),
+            U256::ZERO,
+            data.into(),
+            paymaster_fee,
+        )
+        .await
+    }
+
+    /// Submits a signed token transfer, failing if the token returned `false`
+    pub async fn token_withdraw(
+        &self,
+        key_id: &str,
+        signing_data: &SigningData,
+        public_key: &str,
+        signature: &str,
+    ) -> Result<Execution> {
+        let execution = self
+            .execute_with_sig(key_id, signing_data, public_key, signature)
+            .await?;
+        // Some tokens return nothing from transfer; only an explicit false is a failure
+        if !execution.returndata.is_empty()
+            && !IERC20::transferCall::abi_decode_returns(&execution.returndata).unwrap_or(true)
+        {
+            return Err(Error::Reverted {
+                reason: Some("token transfer returned false".to_string()),
+                data: Some(execution.returndata),
+            });
+        }
+        Ok(execution)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::Network;
+    use alloy::{
+        primitives::{Bytes, address},
+        providers::{ProviderBuilder, mock::Asserter},
+        sol_types::SolValue,
+    };
+
+    #[tokio::test]
+    async fn test_token_info() -> Result<()> {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        let client = GpgWalletClient::new(provider, Network::tea_sepolia());
+        asserter.push_success(&Bytes::from("USDC".to_string().abi_encode()));
+        asserter.push_success(&Bytes::from(U256::from(6).abi_encode()));
+        let token = address!("0x590b78eaF98053eFBa4107Eed2e0F70D2B90A45d");
+        let info = client.token_info(&token.to_string()).await?;
+        assert_eq!(
+            info,
+            TokenInfo {
+                address: token,
+                symbol: "USDC".to_string(),
+                decimals: 6,
+            }
+        );
+        assert_eq!(info.format(U256::from(1_250_000)), "1.25 USDC");
+        Ok(())
+    }
+}
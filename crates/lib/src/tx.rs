@@ -0,0 +1,255 @@
+use alloy::{
+    primitives::U256,
+    providers::Provider,
+    rpc::types::{TransactionReceipt, TransactionRequest},
+};
+use std::sync::atomic::Ordering;
+
+use crate::{Error, Result, client::GpgWalletClient};
+
+/// Overrides for the transactions a client sends; anything unset is left to
+/// the provider's fillers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TxOptions {
+    pub gas_limit: Option<u64>,
+    /// The gas price itself for legacy transactions
+    pub max_fee_per_gas: Option<u128>,
+    pub max_priority_fee_per_gas: Option<u128>,
+    /// Send type 0 transactions with a gas price, for chains without EIP-1559
+    pub legacy: bool,
+    /// The first transaction's nonce; any later ones follow on from it
+    pub nonce: Option<u64>,
+    /// Refuse to send when gas limit times max fee per gas would exceed this
+    pub max_total_fee: Option<U256>,
+}
+
+/// The most a transaction can cost in gas
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeEstimate {
+    pub gas_limit: u64,
+    /// The gas price for legacy transactions
+    pub max_fee_per_gas: u128,
+    /// None for legacy transactions
+    pub max_priority_fee_per_gas: Option<u128>,
+}
+
+impl FeeEstimate {
+    pub fn total(&self) -> U256 {
+        U256::from(self.gas_limit) * U256::from(self.max_fee_per_gas)
+    }
+}
+
+impl<P: Provider> GpgWalletClient<P> {
+    /// Applies the client's [`TxOptions`] to `tx`, failing if it would cost
+    /// more than `max_total_fee`
+    pub async fn prepare_transaction(
+        &self,
+        mut tx: TransactionRequest,
+    ) -> Result<TransactionRequest> {
+        let options = self.tx_options();
+        if tx.from.is_none() {
+            tx.from = self.sender();
+        }
+        if let Some(gas_limit) = options.gas_limit {
+            tx.gas = Some(gas_limit);
+        }
+        if options.legacy {
+            let gas_price = match options.max_fee_per_gas {
+                Some(gas_price) => gas_price,
+                None => self.provider().get_gas_price().await?,
+            };
+            tx.gas_price = Some(gas_price);
+            tx.max_fee_per_gas = None;
+            tx.max_priority_fee_per_gas = None;
+            tx.transaction_type = Some(0);
+        } else if options.max_fee_per_gas.is_some() || options.max_priority_fee_per_gas.is_some() {
+            let (max_fee, priority_fee) =
+                match (options.max_fee_per_gas, options.max_priority_fee_per_gas) {
+                    (Some(max_fee), Some(priority_fee)) => (max_fee, priority_fee),
+                    (max_fee, priority_fee) => {
+                        let estimate = self.provider().estimate_eip1559_fees().await?;
+                        let priority_fee =
+                            priority_fee.unwrap_or(estimate.max_priority_fee_per_gas);
+                        (
+                            max_fee.unwrap_or(estimate.max_fee_per_gas.max(priority_fee)),
+                            priority_fee,
+                        )
+                    }
+                };
+            if priority_fee > max_fee {
+                return Err(Error::InvalidInput {
+                    what: "transaction fees",
+                    reason: format!(
+                        "priority fee {priority_fee} exceeds the max fee per gas {max_fee}"
+                    ),
+                });
+            }
+            tx.max_fee_per_gas = Some(max_fee);
+            tx.max_priority_fee_per_gas = Some(priority_fee);
+        }
+        if let Some(limit) = options.max_total_fee {
+            // Send exactly what was checked, rather than letting the fillers re-estimate
+            let estimate = self.estimate_fee(&tx).await?;
+            if estimate.total() > limit {
+                return Err(Error::FeeTooHigh {
+                    estimated: estimate.total(),
+                    limit,
+                });
+            }
+            tx.gas = Some(estimate.gas_limit);
+            match estimate.max_priority_fee_per_gas {
+                Some(priority_fee) => {
+                    tx.max_fee_per_gas = Some(estimate.max_fee_per_gas);
+                    tx.max_priority_fee_per_gas = Some(priority_fee);
+                }
+                None => tx.gas_price = Some(estimate.max_fee_per_gas),
+            }
+        }
+        Ok(tx)
+    }
+
+    /// The gas limit and fees `tx` would be sent with, estimating whatever it leaves unset
+    pub async fn estimate_fee(&self, tx: &TransactionRequest) -> Result<FeeEstimate> {
+        let gas_limit = match tx.gas {
+            Some(gas_limit) => gas_limit,
+            None => self.provider().estimate_gas(tx.clone()).await?,
+        };
+        if let Some(gas_price) = tx.gas_price {
+            return Ok(FeeEstimate {
+                gas_limit,
+                max_fee_per_gas: gas_price,
+                max_priority_fee_per_gas: None,
+            });
+        }
+        let (max_fee, priority_fee) = match (tx.max_fee_per_gas, tx.max_priority_fee_per_gas) {
+            (Some(max_fee), Some(priority_fee)) => (max_fee, priority_fee),
+            _ => {
+                let estimate = self.provider().estimate_eip1559_fees().await?;
+                (estimate.max_fee_per_gas, estimate.max_priority_fee_per_gas)
+            }
+        };
+        Ok(FeeEstimate {
+            gas_limit,
+            max_fee_per_gas: max_fee,
+            max_priority_fee_per_gas: Some(priority_fee),
+        })
+    }
+
+    /// Prepares, sends and waits for `tx`, failing if it reverts
+    pub(crate) async fn send_transaction(
+        &self,
+        tx: TransactionRequest,
+    ) -> Result<TransactionReceipt> {
+        let mut tx = self.prepare_transaction(tx).await?;
+        let nonce = self.tx_options().nonce;
+        if let Some(nonce) = nonce {
+            tx.nonce = Some(nonce + self.sent.load(Ordering::Relaxed));
+        }
+        let pending = self.provider().send_transaction(tx).await?;
+        // Only a transaction the node accepted uses up its nonce
+        if nonce.is_some() {
+            self.sent.fetch_add(1, Ordering::Relaxed);
+        }
+        let receipt = pending.get_receipt().await?;
+        if !receipt.status() {
+            return Err(Error::TransactionReverted(receipt.transaction_hash));
+        }
+        Ok(receipt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::Network;
+    use alloy::{
+        primitives::{Address, TxHash, U64, address},
+        providers::{ProviderBuilder, mock::Asserter},
+    };
+
+    const GWEI: u128 = 1_000_000_000;
+
+    #[tokio::test]
+    async fn test_prepare_transaction() -> Result<()> {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        let tx = TransactionRequest::default()
+            .to(address!("0xd7baae85d719c2e8e27a70194471ef4b6b253d33"))
+            .value(U256::from(1));
+
+        // Fully specified: no RPC calls, and exactly at the limit
+        let options = TxOptions {
+            gas_limit: Some(100_000),
+            max_fee_per_gas: Some(10 * GWEI),
+            max_priority_fee_per_gas: Some(GWEI),
+            max_total_fee: Some(U256::from(100_000 * 10 * GWEI)),
+            ..Default::default()
+        };
+        let client =
+            GpgWalletClient::new(provider, Network::tea_sepolia()).with_tx_options(options);
+        let prepared = client.prepare_transaction(tx.clone()).await?;
+        assert_eq!(prepared.gas, Some(100_000));
+        assert_eq!(prepared.max_fee_per_gas, Some(10 * GWEI));
+        assert_eq!(prepared.max_priority_fee_per_gas, Some(GWEI));
+
+        let client = client.with_tx_options(TxOptions {
+            max_total_fee: Some(U256::from(100_000 * 10 * GWEI - 1)),
+            ..options
+        });
+        assert!(matches!(
+            client.prepare_transaction(tx.clone()).await,
+            Err(Error::FeeTooHigh { .. })
+        ));
+
+        let client = client.with_tx_options(TxOptions {
+            max_priority_fee_per_gas: Some(20 * GWEI),
+            ..options
+        });
+        assert!(client.prepare_transaction(tx.clone()).await.is_err());
+
+        // Legacy with the gas price and limit estimated
+        let client = client.with_tx_options(TxOptions {
+            legacy: true,
+            max_total_fee: Some(U256::from(GWEI) * U256::from(21_000)),
+            ..Default::default()
+        });
+        asserter.push_success(&U256::from(GWEI));
+        asserter.push_success(&U64::from(21_000));
+        let prepared = client.prepare_transaction(tx).await?;
+        assert_eq!(prepared.transaction_type, Some(0));
+        assert_eq!(prepared.gas_price, Some(GWEI));
+        assert_eq!(prepared.gas, Some(21_000));
+        assert_eq!(prepared.max_fee_per_gas, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_send_transaction_nonce() -> Result<()> {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        let client = GpgWalletClient::new(provider, Network::tea_sepolia())
+            .with_tx_options(TxOptions {
+                gas_limit: Some(21_000),
+                max_fee_per_gas: Some(2 * GWEI),
+                max_priority_fee_per_gas: Some(GWEI),
+                nonce: Some(7),
+                ..Default::default()
+            })
+            .with_sender(Address::repeat_byte(0xaa));
+        let tx = TransactionRequest::default()
+            .to(address!("0xd7baae85d719c2e8e27a70194471ef4b6b253d33"))
+            .value(U256::from(1));
+
+        // Rejected by the node: the next send reuses nonce 7
+        asserter.push_success(&U64::from(10218));
+        asserter.push_failure_msg("nonce too low");
+        assert!(client.send_transaction(tx.clone()).await.is_err());
+        assert_eq!(client.sent.load(Ordering::Relaxed), 0);
+
+        // Accepted, even though its receipt can't be fetched: nonce 7 is spent
+        asserter.push_success(&TxHash::repeat_byte(0x11));
+        assert!(client.send_transaction(tx).await.is_err());
+        assert_eq!(client.sent.load(Ordering::Relaxed), 1);
+        Ok(())
+    }
+}
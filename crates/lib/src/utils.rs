@@ -1,54 +1,135 @@
-use reqwest::Url as URL;
-use std::str::FromStr;
+use std::{str::FromStr, time::SystemTime};
 
-use alloy::primitives::{FixedBytes, U256};
-use anyhow::{Context, Result, anyhow};
+use alloy::primitives::{Address, Bytes, FixedBytes, U256};
+
+use crate::{Error, Result};
 
 pub const WEI_PER_ETH: u128 = 1_000_000_000_000_000_000;
 pub const ETH_DECIMALS: usize = 18;
 
 #[inline]
-pub fn get_rpc_url() -> Result<URL> {
-    env!("RPC_URL").parse().context("Invalid RPC URL")
+pub fn key_id_to_bytes(key_id: &str) -> Result<FixedBytes<8>> {
+    FixedBytes::from_str(key_id).map_err(|_| Error::InvalidKeyId(key_id.to_string()))
 }
 
 #[inline]
-pub fn key_id_to_bytes(key_id: &str) -> Result<FixedBytes<8>> {
-    FixedBytes::from_str(key_id).context("Failed to convert key id to FixedBytes")
+pub fn parse_address(address: &str) -> Result<Address> {
+    Address::from_str(address).map_err(|_| Error::InvalidAddress(address.to_string()))
+}
+
+#[inline]
+pub fn parse_bytes(what: &'static str, hex: &str) -> Result<Bytes> {
+    Bytes::from_str(hex).map_err(|e| Error::InvalidInput {
+        what,
+        reason: e.to_string(),
+    })
+}
+
+/// Seconds since the Unix epoch, as used for signature deadlines
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
 }
 
 /// Convert a decimal string to U256 representing wei with higher precision
 /// This method handles larger amounts accurately
 pub fn decimal_to_wei_precise(amount_str: &str) -> Result<U256> {
+    decimal_to_units(amount_str, ETH_DECIMALS)
+}
+
+/// Convert a decimal string to U256 in a token's smallest unit,
+/// e.g. "1.5" with 6 decimals is 1_500_000
+pub fn decimal_to_units(amount_str: &str, decimals: usize) -> Result<U256> {
+    let invalid = || Error::InvalidAmount(amount_str.to_string());
+    let scale = U256::from(10).pow(U256::from(decimals));
     // Split on decimal point
     let parts: Vec<&str> = amount_str.split('.').collect();
 
     match parts.as_slice() {
         [whole] => {
-            // No decimal part, just multiply by 10^18
-            let whole_u256 =
-                U256::from_str_radix(whole, 10).context("Failed to parse whole number")?;
-            Ok(whole_u256 * U256::from(WEI_PER_ETH))
+            // No decimal part, just multiply by 10^decimals
+            let whole_u256 = U256::from_str_radix(whole, 10).map_err(|_| invalid())?;
+            whole_u256.checked_mul(scale).ok_or_else(invalid)
         }
         [whole, decimal] => {
             // Has decimal part
-            let whole_u256 =
-                U256::from_str_radix(whole, 10).context("Failed to parse whole number")?;
+            let whole_u256 = U256::from_str_radix(whole, 10).map_err(|_| invalid())?;
 
-            // Pad decimal part to 18 digits
+            // Pad decimal part to `decimals` digits
             let mut padded_decimal = decimal.to_string();
-            while padded_decimal.len() < ETH_DECIMALS {
+            while padded_decimal.len() < decimals {
                 padded_decimal.push('0');
             }
-            if padded_decimal.len() > ETH_DECIMALS {
-                padded_decimal.truncate(ETH_DECIMALS);
+            if padded_decimal.len() > decimals {
+                padded_decimal.truncate(decimals);
             }
 
-            let decimal_u256 = U256::from_str_radix(&padded_decimal, 10)
-                .context("Failed to parse decimal part")?;
+            let decimal_u256 = if padded_decimal.is_empty() {
+                U256::ZERO
+            } else {
+                U256::from_str_radix(&padded_decimal, 10).map_err(|_| invalid())?
+            };
 
-            Ok(whole_u256 * U256::from(WEI_PER_ETH) + decimal_u256)
+            whole_u256
+                .checked_mul(scale)
+                .and_then(|whole| whole.checked_add(decimal_u256))
+                .ok_or_else(invalid)
         }
-        _ => Err(anyhow!("Invalid decimal format")),
+        _ => Err(invalid()),
+    }
+}
+
+/// Convert a U256 in a token's smallest unit to a decimal string,
+/// removing trailing zeros and unnecessary decimal places
+pub fn units_to_decimal(value: U256, decimals: usize) -> String {
+    let value_str = value.to_string();
+    if decimals == 0 {
+        return value_str;
+    }
+
+    let padded = if value_str.len() <= decimals {
+        // Pad with leading zeros so there's a whole part
+        format!("{}{value_str}", "0".repeat(decimals + 1 - value_str.len()))
+    } else {
+        value_str
+    };
+    let (whole, fraction) = padded.split_at(padded.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{whole}.{fraction}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decimal_to_units() -> Result<()> {
+        assert_eq!(
+            decimal_to_wei_precise("1.5")?,
+            U256::from(1_500_000_000_000_000_000u128)
+        );
+        assert_eq!(decimal_to_units("1.5", 6)?, U256::from(1_500_000));
+        assert_eq!(decimal_to_units("0.0000001", 6)?, U256::ZERO);
+        assert_eq!(decimal_to_units("42", 0)?, U256::from(42));
+        assert_eq!(decimal_to_units("42.9", 0)?, U256::from(42));
+        assert!(decimal_to_units("1.2.3", 6).is_err());
+        assert!(decimal_to_units("abc", 6).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_units_to_decimal() {
+        assert_eq!(units_to_decimal(U256::from(1_500_000), 6), "1.5");
+        assert_eq!(units_to_decimal(U256::from(1), 6), "0.000001");
+        assert_eq!(units_to_decimal(U256::ZERO, 18), "0");
+        assert_eq!(units_to_decimal(U256::from(100_000_000), 6), "100");
+        assert_eq!(units_to_decimal(U256::from(42), 0), "42");
     }
 }
@@ -0,0 +1,173 @@
+use alloy::{
+    primitives::{Address, FixedBytes, hex},
+    providers::Provider,
+};
+use pgp::{
+    composed::{Deserializable, DetachedSignature},
+    packet::{Packet, PacketParser},
+    types::{KeyDetails, KeyId},
+};
+
+use crate::{Error, Result, client::GpgWalletClient};
+
+/// Checks that `signature` was made by the key the wallet belongs to and
+/// verifies over `message`, so a bad signature fails before it costs gas
+///
+/// `public_key` may be a bare key packet or a whole `gpg --export`; only its
+/// primary key is used.
+pub fn verify_signature(
+    key_id: FixedBytes<8>,
+    message: &[u8],
+    public_key: &[u8],
+    signature: &[u8],
+) -> Result<()> {
+    let expected = KeyId::from(key_id.0);
+    let public_key = PacketParser::new(public_key)
+        .find_map(|packet| match packet {
+            Ok(Packet::PublicKey(key)) => Some(key),
+            _ => None,
+        })
+        .ok_or_else(|| Error::OpenPgp("No public key packet found".to_string()))?;
+    if public_key.legacy_key_id() != expected {
+        return Err(Error::SignatureRejected(format!(
+            "public key is {}, but the wallet belongs to {}",
+            hex::encode_upper(public_key.legacy_key_id()),
+            hex::encode_upper(key_id)
+        )));
+    }
+
+    let (signature, _) = DetachedSignature::from_reader_single(signature)
+        .map_err(|e| Error::OpenPgp(format!("Invalid signature: {e}")))?;
+    let issuers = signature.signature.issuer_key_id();
+    let fingerprints = signature.signature.issuer_fingerprint();
+    let issued_by_key = issuers.iter().any(|issuer| **issuer == expected)
+        || fingerprints
+            .iter()
+            .any(|fingerprint| **fingerprint == public_key.fingerprint());
+    if !issued_by_key {
+        let issuer = issuers
+            .first()
+            .map(hex::encode_upper)
+            .unwrap_or_else(|| "an unnamed key".to_string());
+        // gpg -u picks a signing subkey over the primary key when there is one
+        return Err(Error::SignatureRejected(format!(
+            "signed by {issuer}, but the wallet belongs to {}; sign with the primary key (e.g. gpg -u {}!)",
+            hex::encode_upper(key_id),
+            hex::encode_upper(key_id)
+        )));
+    }
+    signature.verify(&public_key, message).map_err(|e| {
+        Error::SignatureRejected(format!(
+            "signature doesn't verify over the struct hash: {e}"
+        ))
+    })
+}
+
+impl<P: Provider> GpgWalletClient<P> {
+    /// [`verify_signature`] against the key ID `wallet` was deployed for
+    pub async fn verify_signature(
+        &self,
+        wallet: Address,
+        struct_hash: FixedBytes<32>,
+        public_key: &[u8],
+        signature: &[u8],
+    ) -> Result<()> {
+        let key_id = self.wallet(wallet).keyId().call().await?;
+        verify_signature(key_id, struct_hash.as_slice(), public_key, signature)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use pgp::{
+        composed::{KeyType, SecretKeyParamsBuilder, SignedPublicKey, SignedSecretKey},
+        crypto::hash::HashAlgorithm,
+        packet::PacketTrait,
+        ser::Serialize,
+        types::Password,
+    };
+
+    pub(crate) fn generate(passphrase: &str) -> Vec<u8> {
+        let params = SecretKeyParamsBuilder::default()
+            .key_type(KeyType::Ed25519Legacy)
+            .can_certify(true)
+            .can_sign(true)
+            .primary_user_id("Test <test@example.com>".into())
+            .passphrase(Some(passphrase.to_string()))
+            .build()
+            .expect("Invalid key parameters");
+        params
+            .generate(rand::thread_rng())
+            .expect("Failed to generate key")
+            .to_bytes()
+            .expect("Failed to encode key")
+    }
+
+    pub(crate) fn parse(contents: &[u8]) -> SignedSecretKey {
+        SignedSecretKey::from_reader_single(contents)
+            .expect("Failed to parse key")
+            .0
+    }
+
+    pub(crate) fn sign(key: &SignedSecretKey, message: &[u8]) -> Vec<u8> {
+        DetachedSignature::sign_binary_data(
+            rand::thread_rng(),
+            &key.primary_key,
+            &Password::empty(),
+            HashAlgorithm::Sha256,
+            message,
+        )
+        .expect("Failed to sign")
+        .to_bytes()
+        .expect("Failed to encode signature")
+    }
+
+    pub(crate) fn key_id(key: &SignedSecretKey) -> FixedBytes<8> {
+        FixedBytes::from_slice(key.legacy_key_id().as_ref())
+    }
+
+    /// The bare primary key packet, as bpb exports it
+    pub(crate) fn public_key_packet(key: &SignedSecretKey) -> Vec<u8> {
+        let mut packet = Vec::new();
+        key.primary_key
+            .public_key()
+            .to_writer_with_header(&mut packet)
+            .expect("Failed to encode public key");
+        packet
+    }
+
+    #[test]
+    fn test_verify_signature() -> Result<()> {
+        let key = parse(&generate(""));
+        let other = parse(&generate(""));
+        let message = [0xab; 32];
+        let signature = sign(&key, &message);
+        // A whole export, as gpg produces, and the bare key packet, as bpb does
+        let export = SignedPublicKey::from(key.clone())
+            .to_bytes()
+            .expect("Failed to encode public key");
+        let packet = public_key_packet(&key);
+
+        for public_key in [&export, &packet] {
+            verify_signature(key_id(&key), &message, public_key, &signature)?;
+        }
+        assert!(matches!(
+            verify_signature(key_id(&key), &[0; 32], &packet, &signature),
+            Err(Error::SignatureRejected(reason)) if reason.contains("doesn't verify")
+        ));
+        assert!(matches!(
+            verify_signature(key_id(&key), &message, &packet, &sign(&other, &message)),
+            Err(Error::SignatureRejected(reason)) if reason.contains("signed by")
+        ));
+        assert!(matches!(
+            verify_signature(key_id(&other), &message, &packet, &signature),
+            Err(Error::SignatureRejected(reason)) if reason.contains("public key is")
+        ));
+        assert!(matches!(
+            verify_signature(key_id(&key), &message, b"junk", &signature),
+            Err(Error::OpenPgp(_))
+        ));
+        Ok(())
+    }
+}
@@ -1,13 +1,10 @@
 use alloy::{
     primitives::{Address, Bytes, FixedBytes, U256},
-    providers::ProviderBuilder,
-    signers::local::PrivateKeySigner,
     sol,
 };
-use anyhow::{Context, Result};
-use std::{str::FromStr, time::SystemTime};
+use serde::{Deserialize, Serialize};
 
-use crate::{deployer::predict_address, utils::get_rpc_url};
+use crate::{Result, client::GpgWalletClient, network::Network};
 
 sol!(
     #[allow(clippy::too_many_arguments)]
@@ -16,85 +13,137 @@ sol!(
     "abi/GpgRewardWallet.json"
 );
 
+/// Which signed entry point of the wallet a signature authorizes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Operation {
+    /// `withdrawAll(to, paymasterFee, deadline, pubKey, signature)`
+    WithdrawAll,
+    /// `executeWithSig(to, value, data, paymasterFee, deadline, pubKey, signature)`
+    Execute,
+}
+
+/// A struct hash to sign, along with the parameters it commits to
+#[derive(Debug, Clone)]
 pub struct SigningData {
     pub blob: FixedBytes<32>,
+    /// The wallet whose domain the struct hash is for
+    pub wallet: Address,
     pub deadline: U256,
+    pub operation: Operation,
+    pub to: Address,
+    /// Wei to send; unused by `withdrawAll`, which sends the whole balance
+    pub value: U256,
+    /// Calldata for `executeWithSig`; empty for plain transfers
+    pub data: Bytes,
+    /// Wei paid from the wallet to the transaction's submitter
+    pub paymaster_fee: U256,
+    pub nonce: U256,
 }
 
-pub async fn get_signable_hash(key_id: &str, to: &str) -> Result<SigningData> {
-    let provider = ProviderBuilder::new().connect_http(get_rpc_url()?);
-    let destination = predict_address(key_id).await?;
-    if !destination.isDeployed {
-        panic!("GPG wallet for key ID {key_id} is not deployed");
-    }
-    let wallet = GpgRewardWallet::new(destination.walletAddress, provider);
-    let nonce = wallet
-        .nextNonce()
-        .call()
-        .await
-        .context("Failed to get nonce")?;
-    let deadline = U256::from(
-        SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .context("Failed to get current time")?
-            .as_secs()
-            + 120,
-    ); // 2 minutes from now
-    let to = Address::from_str(to).context("Failed to parse destination address")?;
-    let blob = wallet
-        .getWithdrawAllStructHash(to, U256::ZERO, deadline, nonce)
-        .call()
+/// A mined `executeWithSig` call
+#[derive(Debug, Clone)]
+pub struct Execution {
+    pub tx_hash: FixedBytes<32>,
+    /// What the called contract returned, ABI-encoded
+    pub returndata: Bytes,
+}
+
+pub async fn get_signable_hash(
+    network: &Network,
+    key_id: &str,
+    to: &str,
+    paymaster_fee: U256,
+) -> Result<SigningData> {
+    GpgWalletClient::connect(network)
+        .get_signable_hash(key_id, to, paymaster_fee)
         .await
-        .context("Failed to get signable hash")?;
-    Ok(SigningData { blob, deadline })
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn sweep_gpg_key(
+    network: &Network,
     key_id: &str,
     to: &str,
+    paymaster_fee: U256,
     deadline: U256,
     public_key: &str,
     signature: &str,
     private_key: &str,
 ) -> Result<FixedBytes<32>> {
-    let provider = ProviderBuilder::new()
-        .wallet(
-            private_key
-                .parse::<PrivateKeySigner>()
-                .context("Invalid private key")?,
-        )
-        .connect_http(get_rpc_url()?);
-    let destination = predict_address(key_id).await?;
-    if !destination.isDeployed {
-        panic!("GPG wallet for key ID {key_id} is not deployed");
-    }
-    let wallet = GpgRewardWallet::new(destination.walletAddress, provider);
-    let to = Address::from_str(to).context("Failed to parse destination address")?;
-    let pubkey =
-        Bytes::from_str(public_key).context("Failed to convert public key to FixedBytes")?;
-    let signature =
-        Bytes::from_str(signature).context("Failed to convert signature to FixedBytes")?;
-    Ok(wallet
-        .withdrawAll(to, U256::ZERO, deadline, pubkey, signature)
-        .send()
+    GpgWalletClient::connect_with_signer(network, private_key)?
+        .sweep_gpg_key(key_id, to, paymaster_fee, deadline, public_key, signature)
         .await
-        .context("Failed to send withdrawal transaction")?
-        .get_receipt()
+}
+
+pub async fn get_withdraw_signable_hash(
+    network: &Network,
+    key_id: &str,
+    to: &str,
+    amount: U256,
+    paymaster_fee: U256,
+) -> Result<SigningData> {
+    GpgWalletClient::connect(network)
+        .get_withdraw_signable_hash(key_id, to, amount, paymaster_fee)
+        .await
+}
+
+// withdraw part of a gpg wallet's balance via executeWithSig
+// signing_data: from get_withdraw_signable_hash, signed by the wallet's key
+// returns the transaction hash
+pub async fn withdraw_from_gpg_key(
+    network: &Network,
+    key_id: &str,
+    signing_data: &SigningData,
+    public_key: &str,
+    signature: &str,
+    private_key: &str,
+) -> Result<FixedBytes<32>> {
+    GpgWalletClient::connect_with_signer(network, private_key)?
+        .execute_with_sig(key_id, signing_data, public_key, signature)
+        .await
+        .map(|execution| execution.tx_hash)
+}
+
+pub async fn get_execute_signable_hash(
+    network: &Network,
+    key_id: &str,
+    to: &str,
+    value: U256,
+    data: Bytes,
+    paymaster_fee: U256,
+) -> Result<SigningData> {
+    GpgWalletClient::connect(network)
+        .get_execute_signable_hash(key_id, to, value, data, paymaster_fee)
+        .await
+}
+
+// call any contract from a gpg wallet via executeWithSig
+// signing_data: from get_execute_signable_hash, signed by the wallet's key
+// returns the transaction hash and the call's return data
+pub async fn execute_with_sig(
+    network: &Network,
+    key_id: &str,
+    signing_data: &SigningData,
+    public_key: &str,
+    signature: &str,
+    private_key: &str,
+) -> Result<Execution> {
+    GpgWalletClient::connect_with_signer(network, private_key)?
+        .execute_with_sig(key_id, signing_data, public_key, signature)
         .await
-        .context("Withdrawal transaction failed")?
-        .transaction_hash)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::wallet::get_signable_hash;
-    use anyhow::Result;
+    use crate::{Result, network::Network, wallet::get_signable_hash};
+    use alloy::primitives::U256;
 
     #[tokio::test]
     async fn test_get_signable_hash() -> Result<()> {
         let key_id = "95469C7E3DFC90B1";
         let to = "0x590b78eaF98053eFBa4107Eed2e0F70D2B90A45d";
-        get_signable_hash(key_id, to).await?;
+        get_signable_hash(&Network::tea_sepolia(), key_id, to, U256::ZERO).await?;
         Ok(())
     }
 }
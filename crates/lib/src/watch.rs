@@ -0,0 +1,402 @@
+use alloy::{
+    eips::BlockId,
+    primitives::{Address, B256, U256},
+    providers::Provider,
+};
+
+use crate::{
+    Error, Result,
+    client::GpgWalletClient,
+    history::{HistoryEntry, HistoryKind},
+};
+
+/// Blocks a change waits for before it's reported; 1 reports the head itself
+pub const DEFAULT_CONFIRMATIONS: u64 = 2;
+
+/// What a wallet looks like as of one block
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WalletState {
+    pub balance: U256,
+    pub is_deployed: bool,
+    /// Zero until deployment
+    pub next_nonce: U256,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchChange {
+    Deployed,
+    /// A deposit, withdrawal, call or recovery, as `history` reports it
+    Transaction(HistoryEntry),
+    NonceChanged {
+        previous: U256,
+        next_nonce: U256,
+    },
+    /// A balance change the block's `Transaction`s don't account for, such as
+    /// a transfer from a contract
+    BalanceChanged {
+        previous: U256,
+        balance: U256,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchEvent {
+    pub key_id: String,
+    pub wallet: Address,
+    pub block: u64,
+    pub change: WatchChange,
+}
+
+impl WatchEvent {
+    pub fn is_deposit(&self) -> bool {
+        matches!(&self.change, WatchChange::Transaction(entry) if entry.kind == HistoryKind::Deposit)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WatchedWallet {
+    pub key_id: String,
+    pub address: Address,
+    pub state: WalletState,
+}
+
+/// Follows confirmed blocks for a set of wallets, one block at a time
+///
+/// Its state only moves past a block once the whole block has been
+/// processed, so after an RPC failure the same watcher can carry on from
+/// wherever it stopped, with a new client if need be.
+#[derive(Debug, Clone)]
+pub struct Watcher {
+    wallets: Vec<WatchedWallet>,
+    confirmations: u64,
+    /// The last block processed and its hash, once started
+    last_block: Option<(u64, B256)>,
+    /// Set by a reorg, so re-anchoring reports what changed since the
+    /// states from before it
+    resync: bool,
+}
+
+impl Watcher {
+    /// `wallets` are key IDs and their predicted addresses
+    pub fn new(wallets: Vec<(String, Address)>, confirmations: u64) -> Self {
+        Watcher {
+            wallets: wallets
+                .into_iter()
+                .map(|(key_id, address)| WatchedWallet {
+                    key_id,
+                    address,
+                    state: WalletState::default(),
+                })
+                .collect(),
+            confirmations: confirmations.max(1),
+            last_block: None,
+            resync: false,
+        }
+    }
+
+    pub fn wallets(&self) -> &[WatchedWallet] {
+        &self.wallets
+    }
+
+    /// The last block processed, once started
+    pub fn last_block(&self) -> Option<u64> {
+        self.last_block.map(|(number, _)| number)
+    }
+
+    /// The newest block with enough confirmations, given the chain head
+    pub fn confirmed(&self, head: u64) -> Option<u64> {
+        (head + 1).checked_sub(self.confirmations)
+    }
+
+    /// Processes the next confirmed block, returning its events; None once
+    /// caught up with `head`
+    ///
+    /// The first call only records each wallet's state at the newest
+    /// confirmed block, and returns no events. A reorg deeper than the
+    /// confirmations fails with [`Error::Reorg`]; the next call re-anchors on
+    /// the new chain and reports how each wallet's state changed since the
+    /// last block processed, without the individual transactions.
+    pub async fn advance<P: Provider>(
+        &mut self,
+        client: &GpgWalletClient<P>,
+        head: u64,
+    ) -> Result<Option<Vec<WatchEvent>>> {
+        let Some(confirmed) = self.confirmed(head) else {
+            return Ok(None);
+        };
+        let Some((last, last_hash)) = self.last_block else {
+            let block = client.full_block(confirmed).await?;
+            let mut events = Vec::new();
+            let mut states = Vec::with_capacity(self.wallets.len());
+            for wallet in &self.wallets {
+                let state = client.wallet_state(wallet.address, confirmed).await?;
+                if self.resync {
+                    events.extend(
+                        state_changes(wallet.state, state, Vec::new())
+                            .into_iter()
+                            .map(|change| wallet.event(confirmed, change)),
+                    );
+                }
+                states.push(state);
+            }
+            for (wallet, state) in self.wallets.iter_mut().zip(states) {
+                wallet.state = state;
+            }
+            self.last_block = Some((confirmed, block.header.hash));
+            self.resync = false;
+            return Ok(Some(events));
+        };
+        if last >= confirmed {
+            return Ok(None);
+        }
+
+        let number = last + 1;
+        let block = client.full_block(number).await?;
+        if block.header.parent_hash != last_hash {
+            self.last_block = None;
+            self.resync = true;
+            return Err(Error::Reorg(last));
+        }
+        let mut events = Vec::new();
+        let mut states = Vec::with_capacity(self.wallets.len());
+        for wallet in &self.wallets {
+            let state = client.wallet_state(wallet.address, number).await?;
+            let entries = client.block_entries(wallet.address, &block).await?;
+            events.extend(
+                state_changes(wallet.state, state, entries)
+                    .into_iter()
+                    .map(|change| wallet.event(number, change)),
+            );
+            states.push(state);
+        }
+        for (wallet, state) in self.wallets.iter_mut().zip(states) {
+            wallet.state = state;
+        }
+        self.last_block = Some((number, block.header.hash));
+        Ok(Some(events))
+    }
+}
+
+impl WatchedWallet {
+    fn event(&self, block: u64, change: WatchChange) -> WatchEvent {
+        WatchEvent {
+            key_id: self.key_id.clone(),
+            wallet: self.address,
+            block,
+            change,
+        }
+    }
+}
+
+/// How a wallet went from `previous` to `state`, with the transactions seen
+/// in between; the balance change is only reported when they don't explain it
+fn state_changes(
+    previous: WalletState,
+    state: WalletState,
+    entries: Vec<HistoryEntry>,
+) -> Vec<WatchChange> {
+    let mut changes = Vec::new();
+    if state.is_deployed && !previous.is_deployed {
+        changes.push(WatchChange::Deployed);
+    }
+    let explained = entries.iter().fold(previous.balance, |balance, entry| {
+        entry.balance_after(balance)
+    });
+    changes.extend(entries.into_iter().map(WatchChange::Transaction));
+    if state.next_nonce != previous.next_nonce {
+        changes.push(WatchChange::NonceChanged {
+            previous: previous.next_nonce,
+            next_nonce: state.next_nonce,
+        });
+    }
+    if state.balance != explained {
+        changes.push(WatchChange::BalanceChanged {
+            previous: previous.balance,
+            balance: state.balance,
+        });
+    }
+    changes
+}
+
+impl<P: Provider> GpgWalletClient<P> {
+    pub async fn wallet_state(&self, wallet: Address, block: u64) -> Result<WalletState> {
+        let block = BlockId::number(block);
+        let balance = self.provider().get_balance(wallet).block_id(block).await?;
+        let is_deployed = !self
+            .provider()
+            .get_code_at(wallet)
+            .block_id(block)
+            .await?
+            .is_empty();
+        let next_nonce = if is_deployed {
+            self.wallet(wallet).nextNonce().block(block).call().await?
+        } else {
+            U256::ZERO
+        };
+        Ok(WalletState {
+            balance,
+            is_deployed,
+            next_nonce,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::Network;
+    use alloy::{
+        consensus,
+        primitives::{Bytes, address},
+        providers::{ProviderBuilder, mock::Asserter},
+        rpc::types::{Block, Header},
+        sol_types::SolValue,
+    };
+
+    const WALLET: Address = address!("0xd7baae85d719c2e8e27a70194471ef4b6b253d33");
+
+    fn block(number: u64, parent: u8) -> Block {
+        Block {
+            header: Header {
+                hash: B256::repeat_byte(number as u8),
+                inner: consensus::Header {
+                    number,
+                    parent_hash: B256::repeat_byte(parent),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn deposit(amount: u64, success: bool) -> HistoryEntry {
+        HistoryEntry {
+            block: 11,
+            timestamp: 0,
+            tx_hash: B256::repeat_byte(0x11),
+            kind: HistoryKind::Deposit,
+            from: Address::repeat_byte(0xaa),
+            to: WALLET,
+            amount: U256::from(amount),
+            paymaster_fee: U256::ZERO,
+            nonce: None,
+            data: Bytes::new(),
+            success,
+        }
+    }
+
+    #[test]
+    fn test_state_changes() {
+        let previous = WalletState {
+            balance: U256::from(5),
+            is_deployed: true,
+            next_nonce: U256::from(1),
+        };
+        let state = |balance: u64| WalletState {
+            balance: U256::from(balance),
+            ..previous
+        };
+
+        // A deposit that accounts for the whole change is reported once
+        assert_eq!(
+            state_changes(previous, state(8), vec![deposit(3, true)]),
+            vec![WatchChange::Transaction(deposit(3, true))]
+        );
+        // More arrived than the deposit, e.g. from a contract in the same block
+        assert_eq!(
+            state_changes(previous, state(10), vec![deposit(3, true)]),
+            vec![
+                WatchChange::Transaction(deposit(3, true)),
+                WatchChange::BalanceChanged {
+                    previous: U256::from(5),
+                    balance: U256::from(10),
+                },
+            ]
+        );
+        // A reverted deposit moved nothing
+        assert_eq!(
+            state_changes(previous, state(5), vec![deposit(3, false)]),
+            vec![WatchChange::Transaction(deposit(3, false))]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_watcher_advance() -> Result<()> {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        let client = GpgWalletClient::new(provider, Network::tea_sepolia());
+        let mut watcher = Watcher::new(vec![("95469C7E3DFC90B1".to_string(), WALLET)], 2);
+        assert_eq!(watcher.confirmed(0), None);
+        assert_eq!(watcher.confirmed(11), Some(10));
+
+        // Starts at block 10: funded, not deployed
+        asserter.push_success(&block(10, 9));
+        asserter.push_success(&U256::from(5));
+        asserter.push_success(&Bytes::new());
+        assert_eq!(watcher.advance(&client, 11).await?, Some(Vec::new()));
+        assert_eq!(watcher.last_block(), Some(10));
+        assert_eq!(watcher.advance(&client, 11).await?, None);
+
+        // Deployed in block 11 with a nonce already used, and topped up from a contract
+        asserter.push_success(&block(11, 10));
+        asserter.push_success(&U256::from(8));
+        asserter.push_success(&Bytes::from_static(&[0x60, 0x01]));
+        asserter.push_success(&Bytes::from(U256::from(1).abi_encode()));
+        let events = watcher.advance(&client, 12).await?.unwrap_or_default();
+        let changes = events
+            .iter()
+            .map(|event| event.change.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            vec![
+                WatchChange::Deployed,
+                WatchChange::NonceChanged {
+                    previous: U256::ZERO,
+                    next_nonce: U256::from(1),
+                },
+                WatchChange::BalanceChanged {
+                    previous: U256::from(5),
+                    balance: U256::from(8),
+                },
+            ]
+        );
+        assert!(
+            events
+                .iter()
+                .all(|event| event.block == 11 && !event.is_deposit())
+        );
+        assert!(watcher.wallets()[0].state.is_deployed);
+
+        // Block 12 doesn't follow the block 11 we saw
+        asserter.push_success(&block(12, 0xee));
+        assert!(matches!(
+            watcher.advance(&client, 13).await,
+            Err(Error::Reorg(11))
+        ));
+        assert_eq!(watcher.last_block(), None);
+
+        // Re-anchored at block 13 on the new chain, where a deposit has landed
+        asserter.push_success(&block(13, 12));
+        asserter.push_success(&U256::from(20));
+        asserter.push_success(&Bytes::from_static(&[0x60, 0x01]));
+        asserter.push_success(&Bytes::from(U256::from(1).abi_encode()));
+        let events = watcher.advance(&client, 14).await?.unwrap_or_default();
+        assert_eq!(
+            events
+                .iter()
+                .map(|event| (event.block, event.change.clone()))
+                .collect::<Vec<_>>(),
+            vec![(
+                13,
+                WatchChange::BalanceChanged {
+                    previous: U256::from(8),
+                    balance: U256::from(20),
+                }
+            )]
+        );
+        assert_eq!(watcher.last_block(), Some(13));
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+mod server;
+
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::{Context, Result};
+use clap::{Arg, command};
+use libtea_gpg_wallet::{
+    client::GpgWalletClient,
+    config::{Config, Overrides},
+};
+use std::path::PathBuf;
+use tokio::net::TcpListener;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // rustls + ring only (no aws-lc C build). reqwest is built with
+    // rustls-no-provider; install ring process-wide before any TLS call.
+    let _ = rustls::crypto::ring::default_provider().install_default();
+    let m = command!()
+        .about("Submits signed GPG wallet withdrawals, paying gas from its own key\n  (reads the private key from the PRIVATE_KEY environment variable)")
+        .arg(
+            Arg::new("listen")
+                .long("listen")
+                .short('l')
+                .default_value("127.0.0.1:8080")
+                .help("Address to serve HTTP on"),
+        )
+        .arg(
+            Arg::new("config")
+                .long("config")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("Path to a TOML config file (default: ~/.config/tea-gpg-wallet/config.toml)"),
+        )
+        .arg(
+            Arg::new("network")
+                .long("network")
+                .short('n')
+                .help("Network profile to use (default: tea-sepolia)"),
+        )
+        .arg(
+            Arg::new("rpc_url")
+                .long("rpc-url")
+                .help("RPC endpoint to use, overriding the config file and environment"),
+        )
+        .arg(
+            Arg::new("deployer")
+                .long("deployer")
+                .help("GPG wallet deployer contract address, overriding the config file and environment"),
+        )
+        .get_matches();
+
+    let config = Config::load(&Overrides {
+        config_path: m.get_one::<PathBuf>("config").cloned(),
+        network: m.get_one::<String>("network").cloned(),
+        rpc_url: m.get_one::<String>("rpc_url").cloned(),
+        deployer: m.get_one::<String>("deployer").cloned(),
+    })?;
+    let network = &config.network.value;
+
+    let private_key =
+        std::env::var("PRIVATE_KEY").context("PRIVATE_KEY environment variable not set")?;
+    let relayer_address = private_key
+        .parse::<PrivateKeySigner>()
+        .context("Invalid PRIVATE_KEY")?
+        .address();
+    let client = GpgWalletClient::connect_with_signer(network, &private_key)?;
+    client.verify_chain_id().await?;
+
+    let listen = m
+        .get_one::<String>("listen")
+        .context("Listen address not provided")?;
+    let listener = TcpListener::bind(listen)
+        .await
+        .with_context(|| format!("Failed to listen on {listen}"))?;
+    println!(
+        "Relaying for {} (chain {}) from {relayer_address} on http://{}",
+        network.name,
+        network.chain_id,
+        listener.local_addr()?
+    );
+    axum::serve(listener, server::router(client)).await?;
+    Ok(())
+}
@@ -0,0 +1,447 @@
+use alloy::{primitives::TxHash, providers::DynProvider};
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use libtea_gpg_wallet::{
+    Error,
+    client::GpgWalletClient,
+    relay::{RelayRequest, RelaySubmission, TxStatus},
+};
+use serde_json::json;
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+/// Submissions remembered for `/status`; older ones, and any from before a
+/// restart, are only reported once mined
+const RECENT_SUBMISSIONS: usize = 10_000;
+
+struct Relayer {
+    client: GpgWalletClient<DynProvider>,
+    // Held across validation and sending, so concurrent requests can't race for a nonce
+    submit_lock: tokio::sync::Mutex<()>,
+    submitted: Mutex<Submitted>,
+}
+
+/// The most recent submissions, oldest first
+#[derive(Default)]
+struct Submitted {
+    order: VecDeque<TxHash>,
+    hashes: HashSet<TxHash>,
+}
+
+impl Submitted {
+    fn insert(&mut self, tx_hash: TxHash) {
+        if self.hashes.insert(tx_hash) {
+            self.order.push_back(tx_hash);
+        }
+        while self.order.len() > RECENT_SUBMISSIONS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.hashes.remove(&oldest);
+            }
+        }
+    }
+
+    fn contains(&self, tx_hash: &TxHash) -> bool {
+        self.hashes.contains(tx_hash)
+    }
+}
+
+/// `POST /relay` takes a [`RelayRequest`]; `GET /status/{tx_hash}` reports
+/// on transactions this relayer submitted
+pub fn router(client: GpgWalletClient<DynProvider>) -> Router {
+    Router::new()
+        .route("/relay", post(relay))
+        .route("/status/{tx_hash}", get(status))
+        .with_state(Arc::new(Relayer {
+            client,
+            submit_lock: tokio::sync::Mutex::new(()),
+            submitted: Mutex::new(Submitted::default()),
+        }))
+}
+
+async fn relay(
+    State(relayer): State<Arc<Relayer>>,
+    Json(request): Json<RelayRequest>,
+) -> Result<Json<RelaySubmission>, ApiError> {
+    let submission = {
+        let _guard = relayer.submit_lock.lock().await;
+        relayer.client.submit_relay(&request).await?
+    };
+    relayer
+        .submitted
+        .lock()
+        .expect("Submitted transactions lock poisoned")
+        .insert(submission.tx_hash);
+    println!(
+        "Relayed {:?} for key ID {}: {}",
+        request.operation, request.key_id, submission.tx_hash
+    );
+    Ok(Json(submission))
+}
+
+async fn status(
+    State(relayer): State<Arc<Relayer>>,
+    Path(tx_hash): Path<TxHash>,
+) -> Result<Json<TxStatus>, ApiError> {
+    let known = relayer
+        .submitted
+        .lock()
+        .expect("Submitted transactions lock poisoned")
+        .contains(&tx_hash);
+    if known {
+        return Ok(Json(relayer.client.transaction_status(tx_hash).await?));
+    }
+    // Forgotten or from before a restart: vouch for it only once its receipt
+    // shows it came from the relayer's own key
+    match relayer.client.sent_transaction_status(tx_hash).await? {
+        Some(status) => Ok(Json(status)),
+        None => Err(ApiError::NotFound(tx_hash)),
+    }
+}
+
+enum ApiError {
+    NotFound(TxHash),
+    Wallet(Error),
+}
+
+impl From<Error> for ApiError {
+    fn from(err: Error) -> Self {
+        ApiError::Wallet(err)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            ApiError::NotFound(tx_hash) => (
+                StatusCode::NOT_FOUND,
+                format!("Transaction {tx_hash} was not submitted by this relayer"),
+            ),
+            // The node or the relayer's own key failed; the request may be fine
+            ApiError::Wallet(
+                err @ (Error::Transport(_)
+                | Error::PendingTransaction(_)
+                | Error::Contract(_)
+                | Error::InvalidPrivateKey(_)),
+            ) => {
+                eprintln!("Relay failed: {err}");
+                (StatusCode::BAD_GATEWAY, err.to_string())
+            }
+            ApiError::Wallet(err) => (StatusCode::UNPROCESSABLE_ENTITY, err.to_string()),
+        };
+        (status, Json(json!({ "error": message }))).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{
+        primitives::{Address, B256, Bytes, FixedBytes, U256, address, keccak256},
+        signers::local::PrivateKeySigner,
+        sol_types::{SolCall, SolValue},
+    };
+    use libtea_gpg_wallet::{
+        deployer::GpgRewardDeployer,
+        eip712::{struct_hash, wallet_domain},
+        network::Network,
+        openpgp::SecretKey,
+        relay::RelayerClient,
+        utils::unix_now,
+        wallet::{GpgRewardWallet, Operation, SigningData},
+    };
+    use pgp::{
+        composed::{KeyType, SecretKeyParamsBuilder},
+        ser::Serialize,
+    };
+    use serde_json::Value;
+    use std::{sync::LazyLock, time::Duration};
+    use tokio::net::TcpListener;
+
+    const WALLET: Address = address!("0xd7baae85d719c2e8e27a70194471ef4b6b253d33");
+    // Any key will do; the mock node accepts whatever it signs
+    const PRIVATE_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcaf784d7bf4f2ff80";
+    const GAS_ESTIMATE: u64 = 100_000;
+    const GAS_PRICE: u64 = 10;
+    const NONCE: u64 = 7;
+    // Hashes the mock node hasn't mined, and has mined from some other account
+    const UNMINED_TX: B256 = B256::ZERO;
+    const OTHER_SENDERS_TX: B256 = B256::repeat_byte(0xee);
+
+    // The key the mocked wallet belongs to
+    static KEY: LazyLock<SecretKey> = LazyLock::new(|| {
+        let key = SecretKeyParamsBuilder::default()
+            .key_type(KeyType::Ed25519Legacy)
+            .can_certify(true)
+            .can_sign(true)
+            .primary_user_id("Test <test@example.com>".into())
+            .build()
+            .expect("Invalid key parameters")
+            .generate(rand::thread_rng())
+            .expect("Failed to generate key")
+            .to_bytes()
+            .expect("Failed to encode key");
+        SecretKey::from_bytes(&key, None).expect("Failed to parse key")
+    });
+
+    // The relayer's own account
+    fn sender() -> Address {
+        PRIVATE_KEY
+            .parse::<PrivateKeySigner>()
+            .expect("Invalid private key")
+            .address()
+    }
+
+    // What the mocked wallet reports for a withdrawAll, hashed as the contract does
+    fn withdraw_all_hash(to: Address, paymaster_fee: U256, deadline: U256) -> B256 {
+        struct_hash(
+            &wallet_domain(Network::tea_sepolia().chain_id, WALLET),
+            &SigningData {
+                blob: B256::ZERO,
+                wallet: WALLET,
+                deadline,
+                operation: Operation::WithdrawAll,
+                to,
+                value: U256::ZERO,
+                data: Bytes::new(),
+                paymaster_fee,
+                nonce: U256::from(NONCE),
+            },
+        )
+    }
+
+    async fn serve(router: Router) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind");
+        let url = format!(
+            "http://{}",
+            listener.local_addr().expect("No local address")
+        );
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        url
+    }
+
+    // A JSON-RPC node that answers by method, so call order doesn't matter
+    async fn mock_rpc(Json(request): Json<Value>) -> Json<Value> {
+        let params = &request["params"];
+        let result = match request["method"].as_str().unwrap_or_default() {
+            "eth_chainId" => json!("0x27ea"),
+            "eth_getTransactionCount" => json!("0x0"),
+            "eth_gasPrice" | "eth_maxPriorityFeePerGas" => json!(format!("{GAS_PRICE:#x}")),
+            "eth_estimateGas" => json!(format!("{GAS_ESTIMATE:#x}")),
+            "eth_blockNumber" => json!("0x10"),
+            "eth_feeHistory" => json!({
+                "oldestBlock": "0x10",
+                "baseFeePerGas": ["0x1", "0x1"],
+                "gasUsedRatio": [0.5],
+                "reward": [["0x1"]],
+            }),
+            "eth_call" => {
+                let call = &params[0];
+                let input: Bytes =
+                    serde_json::from_value(call.get("input").unwrap_or(&call["data"]).clone())
+                        .expect("Invalid call input");
+                let output = match <[u8; 4]>::try_from(&input[..4]).expect("Short call input") {
+                    GpgRewardDeployer::predictAddressCall::SELECTOR => {
+                        (WALLET, true).abi_encode_params()
+                    }
+                    GpgRewardWallet::nextNonceCall::SELECTOR => U256::from(NONCE).abi_encode(),
+                    GpgRewardWallet::getWithdrawAllStructHashCall::SELECTOR => {
+                        let call =
+                            GpgRewardWallet::getWithdrawAllStructHashCall::abi_decode(&input)
+                                .expect("Invalid struct hash call");
+                        withdraw_all_hash(call.to, call.paymasterFee, call.deadline).abi_encode()
+                    }
+                    GpgRewardWallet::keyIdCall::SELECTOR => KEY
+                        .key_id()
+                        .parse::<FixedBytes<8>>()
+                        .expect("Invalid key ID")
+                        .abi_encode(),
+                    GpgRewardWallet::withdrawAllCall::SELECTOR => Vec::new(),
+                    selector => panic!("Unexpected eth_call to {selector:?}"),
+                };
+                json!(Bytes::from(output))
+            }
+            "eth_sendRawTransaction" => {
+                let raw: Bytes =
+                    serde_json::from_value(params[0].clone()).expect("Invalid raw transaction");
+                json!(keccak256(raw))
+            }
+            "eth_getTransactionReceipt" if params[0] == json!(UNMINED_TX) => Value::Null,
+            "eth_getTransactionReceipt" => json!({
+                "transactionHash": params[0],
+                "transactionIndex": "0x0",
+                "blockHash": B256::repeat_byte(0x11),
+                "blockNumber": "0x11",
+                "from": if params[0] == json!(OTHER_SENDERS_TX) {
+                    Address::repeat_byte(0xaa)
+                } else {
+                    sender()
+                },
+                "to": WALLET,
+                "cumulativeGasUsed": "0x186a0",
+                "gasUsed": "0x186a0",
+                "effectiveGasPrice": "0xa",
+                "contractAddress": null,
+                "logs": [],
+                "logsBloom": Bytes::from(vec![0u8; 256]),
+                "type": "0x2",
+                "status": "0x1",
+            }),
+            method => {
+                return Json(json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "error": { "code": -32601, "message": format!("{method} not mocked") },
+                }));
+            }
+        };
+        Json(json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }))
+    }
+
+    async fn relayer() -> String {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        let rpc_url = serve(Router::new().route("/", post(mock_rpc))).await;
+        let network = Network {
+            name: "mock".to_string(),
+            rpc_urls: vec![rpc_url.parse().expect("Invalid mock RPC URL")],
+            explorer_url: None,
+            ..Network::tea_sepolia()
+        };
+        let client = GpgWalletClient::connect_with_signer(&network, PRIVATE_KEY)
+            .expect("Invalid private key");
+        serve(router(client)).await
+    }
+
+    fn request(paymaster_fee: u64) -> RelayRequest {
+        let to = address!("0x590b78eaF98053eFBa4107Eed2e0F70D2B90A45d");
+        let paymaster_fee = U256::from(paymaster_fee);
+        let deadline = U256::from(unix_now() + 60);
+        RelayRequest {
+            key_id: "95469C7E3DFC90B1".to_string(),
+            operation: Operation::WithdrawAll,
+            to,
+            value: U256::ZERO,
+            data: Bytes::new(),
+            paymaster_fee,
+            deadline,
+            public_key: KEY
+                .public_key()
+                .expect("Failed to encode public key")
+                .to_string(),
+            signature: KEY
+                .sign(withdraw_all_hash(to, paymaster_fee, deadline).as_slice())
+                .expect("Failed to sign")
+                .to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_relay_and_status() -> Result<(), Box<dyn std::error::Error>> {
+        let url = relayer().await;
+        let client = RelayerClient::new(&url)?;
+
+        let request = request(GAS_ESTIMATE * GAS_PRICE);
+        let submission = client.submit(&request).await?;
+        assert_eq!(
+            submission.struct_hash,
+            withdraw_all_hash(request.to, request.paymaster_fee, request.deadline)
+        );
+        assert_eq!(submission.gas_estimate, GAS_ESTIMATE);
+
+        let status = client
+            .wait_until_mined(
+                submission.tx_hash,
+                Duration::from_millis(10),
+                Duration::from_secs(1),
+            )
+            .await?;
+        assert_eq!(
+            status,
+            TxStatus::Success {
+                block_number: Some(0x11)
+            }
+        );
+
+        for unknown in [UNMINED_TX, OTHER_SENDERS_TX] {
+            let response = reqwest::get(format!("{url}/status/{unknown}")).await?;
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        }
+
+        // After a restart, a mined transaction from the relayer's key is still found
+        let restarted = RelayerClient::new(&relayer().await)?;
+        assert_eq!(
+            restarted.status(submission.tx_hash).await?,
+            TxStatus::Success {
+                block_number: Some(0x11)
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_submitted_is_bounded() {
+        let mut submitted = Submitted::default();
+        for i in 0..=RECENT_SUBMISSIONS as u64 {
+            submitted.insert(B256::from(U256::from(i)));
+        }
+        submitted.insert(B256::from(U256::from(1)));
+        assert_eq!(submitted.order.len(), RECENT_SUBMISSIONS);
+        assert_eq!(submitted.hashes.len(), RECENT_SUBMISSIONS);
+        assert!(!submitted.contains(&B256::from(U256::ZERO)));
+        assert!(submitted.contains(&B256::from(U256::from(RECENT_SUBMISSIONS))));
+    }
+
+    #[tokio::test]
+    async fn test_relay_rejects_low_fee() -> Result<(), Box<dyn std::error::Error>> {
+        let url = relayer().await;
+        let response = reqwest::Client::new()
+            .post(format!("{url}/relay"))
+            .json(&request(GAS_ESTIMATE * GAS_PRICE - 1))
+            .send()
+            .await?;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body: Value = response.json().await?;
+        assert!(
+            body["error"]
+                .as_str()
+                .is_some_and(|e| e.contains("Paymaster fee"))
+        );
+
+        let err = RelayerClient::new(&url)?
+            .submit(&request(GAS_ESTIMATE * GAS_PRICE - 1))
+            .await
+            .expect_err("Low fee accepted");
+        assert!(matches!(err, Error::Relayer(message) if message.contains("Paymaster fee")));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_relay_rejects_bad_signature() -> Result<(), Box<dyn std::error::Error>> {
+        let url = relayer().await;
+        let mut bad = request(GAS_ESTIMATE * GAS_PRICE);
+        bad.signature = KEY
+            .sign(B256::ZERO.as_slice())
+            .expect("Failed to sign")
+            .to_string();
+        for signature in [bad.signature.clone(), "0x1234".to_string()] {
+            let response = reqwest::Client::new()
+                .post(format!("{url}/relay"))
+                .json(&RelayRequest {
+                    signature,
+                    ..bad.clone()
+                })
+                .send()
+                .await?;
+            assert!(response.status().is_client_error());
+        }
+        Ok(())
+    }
+}